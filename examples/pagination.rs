@@ -29,12 +29,14 @@ struct GetPassengers {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct Passenger {
     name: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+#[allow(dead_code)]
 struct PassengersWrapper {
     total_passengers: usize,
     total_pages: usize,
@@ -45,7 +47,7 @@ impl Request for GetPassengers {
     type Data = Self;
     type Response = PassengersWrapper;
 
-    fn endpoint(&self) -> Cow<str> {
+    fn endpoint(&self) -> Cow<'_, str> {
         "/v1/passenger".into()
     }
 
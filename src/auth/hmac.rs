@@ -0,0 +1,220 @@
+//! HMAC request signing.
+//!
+//! Signs a message built from the final request, i.e. after pagination changes have been
+//! applied, and sends the signature along with the timestamp (and optionally a nonce) in headers.
+//! The message is built from a template, in which the following placeholders are replaced:
+//!
+//! - `{method}`: the HTTP method, e.g. `GET`
+//! - `{path}`: the URL path, e.g. `/v1/orders`
+//! - `{query}`: the URL query, without the leading `?`
+//! - `{body}`: the raw request body
+//! - `{timestamp}`: the current Unix time, in seconds or milliseconds
+//! - `{nonce}`: a random hex string that is unique per request
+use super::{sensitive, AuthProvider};
+use crate::error::{Error, Result};
+use ::hmac::digest::KeyInit;
+use ::hmac::{Hmac, Mac};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures::future::{self, BoxFuture};
+use futures::prelude::*;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Request as RawRequest;
+use sha2::{Sha256, Sha512};
+use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_TEMPLATE: &str = "{timestamp}\n{method}\n{path}\n{query}\n{body}";
+
+/// The hash function used to compute the signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha256,
+    Sha512,
+}
+
+/// The encoding of the signature in the header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    Base64,
+}
+
+/// Authentication through HMAC request signatures.
+///
+/// By default, the signature is computed with HMAC-SHA256 over
+/// `"{timestamp}\n{method}\n{path}\n{query}\n{body}"`, hex encoded and sent in the `X-Signature`
+/// header, with the timestamp in seconds in the `X-Timestamp` header.
+#[derive(Clone)]
+pub struct HmacAuth {
+    secret: Vec<u8>,
+    template: String,
+    algorithm: Algorithm,
+    encoding: Encoding,
+    signature_header: String,
+    timestamp_header: String,
+    nonce_header: Option<String>,
+    key: Option<(String, String)>,
+    millis: bool,
+}
+
+impl HmacAuth {
+    /// Sign requests with the given secret.
+    pub fn new<S: AsRef<[u8]>>(secret: S) -> Self {
+        Self {
+            secret: secret.as_ref().to_vec(),
+            template: DEFAULT_TEMPLATE.to_string(),
+            algorithm: Algorithm::Sha256,
+            encoding: Encoding::Hex,
+            signature_header: "X-Signature".to_string(),
+            timestamp_header: "X-Timestamp".to_string(),
+            nonce_header: None,
+            key: None,
+            millis: false,
+        }
+    }
+
+    /// Set the template for the signed message.
+    pub fn template<S: ToString>(mut self, template: S) -> Self {
+        self.template = template.to_string();
+        self
+    }
+
+    /// Set the hash function. Defaults to SHA-256.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the encoding of the signature. Defaults to hex.
+    pub fn encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Set the header the signature is sent in. Defaults to `X-Signature`.
+    pub fn signature_header<S: ToString>(mut self, name: S) -> Self {
+        self.signature_header = name.to_string();
+        self
+    }
+
+    /// Set the header the timestamp is sent in. Defaults to `X-Timestamp`.
+    pub fn timestamp_header<S: ToString>(mut self, name: S) -> Self {
+        self.timestamp_header = name.to_string();
+        self
+    }
+
+    /// Send the nonce in the given header. The nonce is only sent if this is set.
+    pub fn nonce_header<S: ToString>(mut self, name: S) -> Self {
+        self.nonce_header = Some(name.to_string());
+        self
+    }
+
+    /// Send the API key identifying the secret in the given header.
+    pub fn key<S: ToString>(mut self, header: S, key: S) -> Self {
+        self.key = Some((header.to_string(), key.to_string()));
+        self
+    }
+
+    /// Use Unix time in milliseconds instead of seconds for the timestamp.
+    pub fn timestamp_millis(mut self) -> Self {
+        self.millis = true;
+        self
+    }
+
+    fn sign(&self, request: &mut RawRequest) -> Result<()> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let timestamp = if self.millis {
+            now.as_millis().to_string()
+        } else {
+            now.as_secs().to_string()
+        };
+        let nonce = format!("{:016x}", rand::random::<u64>());
+        let message = self.message(request, &timestamp, &nonce)?;
+        let signature = match self.algorithm {
+            Algorithm::Sha256 => mac::<Hmac<Sha256>>(&self.secret, &message),
+            Algorithm::Sha512 => mac::<Hmac<Sha512>>(&self.secret, &message),
+        };
+        let signature = match self.encoding {
+            Encoding::Hex => hex::encode(signature),
+            Encoding::Base64 => STANDARD.encode(signature),
+        };
+
+        let headers = request.headers_mut();
+        headers.insert(header_name(&self.signature_header)?, sensitive(signature)?);
+        headers.insert(
+            header_name(&self.timestamp_header)?,
+            HeaderValue::from_str(&timestamp).expect("Timestamp is ASCII"),
+        );
+        if let Some(name) = &self.nonce_header {
+            headers.insert(
+                header_name(name)?,
+                HeaderValue::from_str(&nonce).expect("Nonce is ASCII"),
+            );
+        }
+        if let Some((name, key)) = &self.key {
+            headers.insert(header_name(name)?, sensitive(key.clone())?);
+        }
+        Ok(())
+    }
+
+    fn message(&self, request: &RawRequest, timestamp: &str, nonce: &str) -> Result<Vec<u8>> {
+        let body = match request.body() {
+            Some(body) => body.as_bytes().ok_or_else(|| Error::Authorization {
+                msg: "streaming request bodies can't be signed".to_string(),
+            })?,
+            None => &[],
+        };
+        let mut message = Vec::new();
+        let mut rest = self.template.as_str();
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or_else(|| Error::Authorization {
+                    msg: format!(
+                        "unclosed placeholder in signing template {:?}",
+                        self.template
+                    ),
+                })?;
+            let value = match &rest[start + 1..end] {
+                "method" => request.method().as_str().as_bytes(),
+                "path" => request.url().path().as_bytes(),
+                "query" => request.url().query().unwrap_or("").as_bytes(),
+                "body" => body,
+                "timestamp" => timestamp.as_bytes(),
+                "nonce" => nonce.as_bytes(),
+                other => {
+                    return Err(Error::Authorization {
+                        msg: format!("unknown placeholder {{{}}} in signing template", other),
+                    })
+                }
+            };
+            message.extend_from_slice(&rest.as_bytes()[..start]);
+            message.extend_from_slice(value);
+            rest = &rest[end + 1..];
+        }
+        message.extend_from_slice(rest.as_bytes());
+        Ok(message)
+    }
+}
+
+impl AuthProvider for HmacAuth {
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>> {
+        future::ready(self.sign(request)).boxed()
+    }
+}
+
+fn mac<M: Mac + KeyInit>(secret: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = <M as Mac>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(message);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn header_name(name: &str) -> Result<HeaderName> {
+    HeaderName::try_from(name).map_err(|_| Error::InvalidHeader {
+        msg: format!("invalid header name {:?}", name),
+    })
+}
@@ -0,0 +1,181 @@
+//! Constructs for authorizing requests.
+//!
+//! The client authorizes every request through an [`AuthProvider`], right before the request is
+//! sent and after any pagination changes have been applied. Static credentials are covered by
+//! the built-in providers in this module, which back `Client::bearer_auth`, `Client::basic_auth`,
+//! `Client::query_auth` and `Client::header_auth`. Credentials from other sources can be used by
+//! implementing `AuthProvider` and passing it to `Client::with_auth`.
+use crate::error::{Error, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures::future::{self, BoxFuture};
+use futures::prelude::*;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Request as RawRequest, StatusCode};
+use std::convert::TryFrom;
+
+pub mod hmac;
+pub mod oauth2;
+pub mod pool;
+pub mod sigv4;
+
+pub use self::hmac::HmacAuth;
+pub use oauth2::ClientCredentials;
+pub use pool::CredentialPool;
+pub use sigv4::SigV4Auth;
+
+/// Trait for types that add credentials to outgoing requests.
+pub trait AuthProvider: Send + Sync {
+    /// Add credentials to the request, e.g. through headers or query parameters.
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>>;
+
+    /// Called with every response to a request authorized by the provider. The request is passed
+    /// without its body. Return `true` to retry the request once with fresh credentials, e.g.
+    /// after refreshing an expired token. Defaults to never retrying.
+    fn on_response<'a>(
+        &'a self,
+        _request: &'a RawRequest,
+        _status: StatusCode,
+    ) -> BoxFuture<'a, bool> {
+        future::ready(false).boxed()
+    }
+
+    /// Names of the query parameters added by the provider that contain secrets. They are
+    /// redacted wherever the client logs or displays a request. Defaults to none.
+    fn sensitive_query_params(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+pub(crate) fn sensitive(value: String) -> Result<HeaderValue> {
+    let mut value = HeaderValue::try_from(value).map_err(|_| Error::InvalidHeader {
+        msg: "invalid credentials".to_string(),
+    })?;
+    value.set_sensitive(true);
+    Ok(value)
+}
+
+/// Bearer token authentication.
+#[derive(Clone, Debug)]
+pub struct Bearer {
+    token: String,
+}
+
+impl Bearer {
+    /// Authenticate with the given bearer token.
+    pub fn new<S: ToString>(token: S) -> Self {
+        Self {
+            token: token.to_string(),
+        }
+    }
+}
+
+impl AuthProvider for Bearer {
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>> {
+        let value = sensitive(format!("Bearer {}", self.token));
+        future::ready(value.map(|value| {
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }))
+        .boxed()
+    }
+}
+
+/// HTTP basic authentication, with an optional password.
+#[derive(Clone, Debug)]
+pub struct Basic {
+    user: String,
+    pass: Option<String>,
+}
+
+impl Basic {
+    /// Authenticate with the given user and optional password.
+    pub fn new<T: Into<Option<S>>, S: ToString>(user: S, pass: T) -> Self {
+        Self {
+            user: user.to_string(),
+            pass: pass.into().map(|x| x.to_string()),
+        }
+    }
+}
+
+impl AuthProvider for Basic {
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>> {
+        let credentials = format!("{}:{}", self.user, self.pass.as_deref().unwrap_or(""));
+        let value = sensitive(format!("Basic {}", STANDARD.encode(credentials)));
+        future::ready(value.map(|value| {
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }))
+        .boxed()
+    }
+}
+
+/// Authentication through one or more query parameters.
+#[derive(Clone, Debug)]
+pub struct Query {
+    pairs: Vec<(String, String)>,
+}
+
+impl Query {
+    /// Authenticate with the given query key-value pairs.
+    pub fn new<S: ToString>(pairs: Vec<(S, S)>) -> Self {
+        let pairs = pairs
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Self { pairs }
+    }
+}
+
+impl AuthProvider for Query {
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>> {
+        request
+            .url_mut()
+            .query_pairs_mut()
+            .extend_pairs(&self.pairs);
+        future::ok(()).boxed()
+    }
+
+    fn sensitive_query_params(&self) -> Vec<String> {
+        self.pairs.iter().map(|(k, _)| k.clone()).collect()
+    }
+}
+
+/// Authentication through one or more custom headers. The header values are marked as
+/// sensitive.
+#[derive(Clone, Debug)]
+pub struct Header {
+    headers: HeaderMap,
+}
+
+impl Header {
+    /// Authenticate with the given header name-value pairs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the header names or values are invalid. Use `ClientBuilder::header_auth`
+    /// to get an error instead.
+    pub fn new<S: ToString>(pairs: Vec<(S, S)>) -> Self {
+        let mut headers = HeaderMap::new();
+        for (k, v) in pairs {
+            headers.insert(
+                HeaderName::try_from(k.to_string()).expect("Failed to create HeaderName"),
+                sensitive(v.to_string()).expect("Failed to create HeaderValue"),
+            );
+        }
+        Self { headers }
+    }
+}
+
+impl From<HeaderMap> for Header {
+    fn from(headers: HeaderMap) -> Self {
+        Self { headers }
+    }
+}
+
+impl AuthProvider for Header {
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>> {
+        for (k, v) in self.headers.iter() {
+            request.headers_mut().insert(k, v.clone());
+        }
+        future::ok(()).boxed()
+    }
+}
@@ -0,0 +1,187 @@
+//! OAuth2 authentication.
+use super::{sensitive, AuthProvider};
+use crate::error::{Error, Result};
+use futures::future::BoxFuture;
+use futures::prelude::*;
+use log::debug;
+use reqwest::header::AUTHORIZATION;
+use reqwest::{Client as ReqwestClient, Request as RawRequest, StatusCode};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+/// Authentication through the OAuth2 client credentials flow.
+///
+/// The provider fetches an access token from the token endpoint before the first request, and
+/// sends it as a bearer token. The token is cached, shared by all clones of the provider (and of
+/// any client using it), and refreshed shortly before it expires or when a request is rejected
+/// with a 401.
+#[derive(Clone)]
+pub struct ClientCredentials {
+    token_url: String,
+    client_id: String,
+    client_secret: String,
+    scopes: Vec<String>,
+    credentials_in_body: bool,
+    refresh_margin: Duration,
+    client: Option<ReqwestClient>,
+    token: Arc<Mutex<Option<Token>>>,
+}
+
+impl ClientCredentials {
+    /// Create new client credentials for the given token endpoint.
+    pub fn new<U: ToString, S: ToString>(token_url: U, client_id: S, client_secret: S) -> Self {
+        Self {
+            token_url: token_url.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            scopes: Vec::new(),
+            credentials_in_body: false,
+            refresh_margin: Duration::from_secs(30),
+            client: None,
+            token: Default::default(),
+        }
+    }
+
+    /// Request the given scope for the access token.
+    pub fn scope<S: ToString>(mut self, scope: S) -> Self {
+        self.scopes.push(scope.to_string());
+        self
+    }
+
+    /// Send the client id and secret in the body of the token request, instead of through basic
+    /// authentication.
+    pub fn credentials_in_body(mut self) -> Self {
+        self.credentials_in_body = true;
+        self
+    }
+
+    /// How long before the token expires it should be refreshed. Defaults to 30 seconds.
+    pub fn refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Use the given Reqwest client for token requests. By default, `Client::oauth2_auth` uses
+    /// the Reqwest client of the `Client`, so token requests go through the same proxy, timeouts
+    /// and TLS settings configured on `ClientBuilder`. When the provider is added in any other
+    /// way, e.g. through `Client::with_auth`, a default Reqwest client is used.
+    pub fn with_reqwest(mut self, client: ReqwestClient) -> Self {
+        self.client = Some(client);
+        self
+    }
+
+    /// Use the given Reqwest client for token requests, unless one was set through `with_reqwest`.
+    pub(crate) fn default_reqwest(mut self, client: ReqwestClient) -> Self {
+        self.client.get_or_insert(client);
+        self
+    }
+
+    /// Returns the cached access token, fetching a new one if it's missing or about to expire.
+    async fn access_token(&self) -> Result<String> {
+        // Holding the lock while fetching makes concurrent requests wait for a single refresh
+        let mut token = self.token.lock().await;
+        let expired = match token.as_ref() {
+            Some(token) => token.expires_at.is_some_and(|t| t <= Instant::now()),
+            None => true,
+        };
+        if expired {
+            *token = Some(self.fetch().await?);
+        }
+        Ok(token.as_ref().unwrap().access_token.clone())
+    }
+
+    async fn fetch(&self) -> Result<Token> {
+        debug!("Fetching access token from {}", self.token_url);
+        let mut form = vec![("grant_type", self.grant_type())];
+        if !self.scopes.is_empty() {
+            form.push(("scope", self.scopes.join(" ")));
+        }
+        let req = self
+            .client
+            .clone()
+            .unwrap_or_default()
+            .post(&self.token_url);
+        let req = if self.credentials_in_body {
+            form.push(("client_id", self.client_id.clone()));
+            form.push(("client_secret", self.client_secret.clone()));
+            req
+        } else {
+            req.basic_auth(&self.client_id, Some(&self.client_secret))
+        };
+        let res = req.form(&form).send().await?;
+        let status = res.status();
+        if !status.is_success() {
+            return Err(Error::Authorization {
+                msg: format!(
+                    "token request failed with status {}: {}",
+                    status,
+                    res.text().await.unwrap_or_default()
+                ),
+            });
+        }
+        let res: TokenResponse = res.json().await?;
+        let expires_at = res.expires_in.map(|expires_in| {
+            Instant::now() + Duration::from_secs(expires_in).saturating_sub(self.refresh_margin)
+        });
+        Ok(Token {
+            access_token: res.access_token,
+            expires_at,
+        })
+    }
+
+    fn grant_type(&self) -> String {
+        "client_credentials".to_string()
+    }
+}
+
+impl AuthProvider for ClientCredentials {
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let token = self.access_token().await?;
+            let value = sensitive(format!("Bearer {}", token))?;
+            request.headers_mut().insert(AUTHORIZATION, value);
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn on_response<'a>(
+        &'a self,
+        request: &'a RawRequest,
+        status: StatusCode,
+    ) -> BoxFuture<'a, bool> {
+        async move {
+            if status != StatusCode::UNAUTHORIZED {
+                return false;
+            }
+            // The token may have expired early or been revoked. Only discard it if it hasn't
+            // already been replaced by a concurrent request.
+            let sent = request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "));
+            let mut token = self.token.lock().await;
+            if token.as_ref().map(|t| t.access_token.as_str()) == sent {
+                debug!("Request unauthorized, refreshing access token");
+                *token = None;
+            }
+            true
+        }
+        .boxed()
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: Option<u64>,
+}
+
+struct Token {
+    access_token: String,
+    expires_at: Option<Instant>,
+}
@@ -0,0 +1,234 @@
+//! Pools of credentials that are rotated across requests.
+use super::{sensitive, AuthProvider};
+use crate::error::{Error, Result};
+use futures::future::{self, BoxFuture};
+use futures::prelude::*;
+use log::debug;
+use reqwest::header::AUTHORIZATION;
+use reqwest::{Request as RawRequest, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How the next credential is picked from a `CredentialPool`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    /// Use the credentials in turn.
+    RoundRobin,
+    /// Use the credential that was throttled or rejected the longest time ago, preferring
+    /// credentials that have never been throttled.
+    LeastRecentlyThrottled,
+}
+
+/// Authentication through a pool of bearer tokens or query keys, e.g. to spread requests over the
+/// quota of several API keys.
+///
+/// Every request uses the next credential from the pool. A credential that is rejected with a
+/// 401 or throttled with a 429 is benched for a while, and the request is retried once with
+/// another credential. If all credentials are benched, requests wait until the first one is
+/// available again. The pool is shared by all clones of the provider (and of any client using
+/// it).
+#[derive(Clone)]
+pub struct CredentialPool {
+    kind: Kind,
+    rotation: Rotation,
+    bench: Duration,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Clone)]
+enum Kind {
+    Bearer,
+    Query(String),
+}
+
+struct State {
+    credentials: Vec<Credential>,
+    next: usize,
+}
+
+struct Credential {
+    value: String,
+    benched_until: Option<Instant>,
+    last_throttled: Option<Instant>,
+    last_used: Option<Instant>,
+}
+
+enum Pick {
+    Credential(String),
+    WaitUntil(Instant),
+    Empty,
+}
+
+impl CredentialPool {
+    /// Rotate through the given bearer tokens.
+    pub fn bearer<S: ToString>(tokens: Vec<S>) -> Self {
+        Self::new(Kind::Bearer, tokens)
+    }
+
+    /// Rotate through the given keys, sent in the query parameter `name`.
+    pub fn query<S: ToString>(name: S, keys: Vec<S>) -> Self {
+        Self::new(Kind::Query(name.to_string()), keys)
+    }
+
+    fn new<S: ToString>(kind: Kind, values: Vec<S>) -> Self {
+        let credentials = values
+            .into_iter()
+            .map(|value| Credential {
+                value: value.to_string(),
+                benched_until: None,
+                last_throttled: None,
+                last_used: None,
+            })
+            .collect();
+        Self {
+            kind,
+            rotation: Rotation::RoundRobin,
+            bench: Duration::from_secs(60),
+            state: Arc::new(Mutex::new(State {
+                credentials,
+                next: 0,
+            })),
+        }
+    }
+
+    /// Set how the next credential is picked. Defaults to `Rotation::RoundRobin`.
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// How long a credential is benched after a 401 or 429. Defaults to 60 seconds.
+    pub fn bench_for(mut self, duration: Duration) -> Self {
+        self.bench = duration;
+        self
+    }
+
+    fn pick(&self) -> Pick {
+        let mut state = self.state.lock().expect("Credential pool lock poisoned");
+        let now = Instant::now();
+        let len = state.credentials.len();
+        let available = |c: &Credential| c.benched_until.is_none_or(|until| until <= now);
+        let index = match self.rotation {
+            Rotation::RoundRobin => (0..len)
+                .map(|i| (state.next + i) % len)
+                .find(|&i| available(&state.credentials[i])),
+            Rotation::LeastRecentlyThrottled => (0..len)
+                .filter(|&i| available(&state.credentials[i]))
+                .min_by_key(|&i| {
+                    let c = &state.credentials[i];
+                    (c.last_throttled, c.last_used)
+                }),
+        };
+        match index {
+            Some(i) => {
+                state.next = (i + 1) % len;
+                state.credentials[i].last_used = Some(now);
+                Pick::Credential(state.credentials[i].value.clone())
+            }
+            None => match state
+                .credentials
+                .iter()
+                .filter_map(|c| c.benched_until)
+                .min()
+            {
+                Some(until) => Pick::WaitUntil(until),
+                None => Pick::Empty,
+            },
+        }
+    }
+
+    /// Returns the credential that was used for the request, if it was sent by this pool.
+    fn used(&self, request: &RawRequest) -> Option<String> {
+        match &self.kind {
+            Kind::Bearer => request
+                .headers()
+                .get(AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.strip_prefix("Bearer "))
+                .map(|v| v.to_string()),
+            Kind::Query(name) => request
+                .url()
+                .query_pairs()
+                .find(|(k, _)| k == name)
+                .map(|(_, v)| v.into_owned()),
+        }
+    }
+}
+
+impl AuthProvider for CredentialPool {
+    fn authorize<'a>(&'a self, request: &'a mut RawRequest) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let credential = loop {
+                match self.pick() {
+                    Pick::Credential(credential) => break credential,
+                    Pick::WaitUntil(until) => {
+                        debug!("All credentials in the pool are benched, waiting");
+                        tokio::time::sleep_until(until).await;
+                    }
+                    Pick::Empty => {
+                        return Err(Error::Authorization {
+                            msg: "credential pool is empty".to_string(),
+                        })
+                    }
+                }
+            };
+            match &self.kind {
+                Kind::Bearer => {
+                    let value = sensitive(format!("Bearer {}", credential))?;
+                    request.headers_mut().insert(AUTHORIZATION, value);
+                }
+                Kind::Query(name) => {
+                    request
+                        .url_mut()
+                        .query_pairs_mut()
+                        .append_pair(name, &credential);
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn on_response<'a>(
+        &'a self,
+        request: &'a RawRequest,
+        status: StatusCode,
+    ) -> BoxFuture<'a, bool> {
+        if !matches!(
+            status,
+            StatusCode::UNAUTHORIZED | StatusCode::TOO_MANY_REQUESTS
+        ) {
+            return future::ready(false).boxed();
+        }
+        let used = self.used(request);
+        let mut state = self.state.lock().expect("Credential pool lock poisoned");
+        let now = Instant::now();
+        if let Some((i, credential)) = state
+            .credentials
+            .iter_mut()
+            .enumerate()
+            .find(|(_, c)| used.as_ref() == Some(&c.value))
+        {
+            debug!(
+                "Credential {} in the pool got status {}, benching it for {:?}",
+                i, status, self.bench
+            );
+            credential.benched_until = Some(now + self.bench);
+            credential.last_throttled = Some(now);
+        }
+        // Only retry if another credential can be used right away
+        let retry = state
+            .credentials
+            .iter()
+            .any(|c| c.benched_until.is_none_or(|until| until <= now));
+        future::ready(retry).boxed()
+    }
+
+    fn sensitive_query_params(&self) -> Vec<String> {
+        match &self.kind {
+            Kind::Bearer => Vec::new(),
+            Kind::Query(name) => vec![name.clone()],
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//! A blocking client for use in synchronous code.
+//!
+//! The blocking client wraps the async [`Client`](crate::Client) and drives it on its own
+//! single-threaded runtime, so the same `Request` and `PaginatedRequest` implementations work
+//! for both clients. The blocking client must not be used from within an async runtime.
+use crate::auth::AuthProvider;
+use crate::error::{Error, Result};
+use crate::pagination::PaginatedRequest;
+use crate::request::Request;
+use futures::prelude::*;
+use reqwest::Client as ReqwestClient;
+use std::convert::TryFrom;
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// The blocking client used for making requests.
+///
+/// Any configuration available on the async client can be applied before converting it into a
+/// blocking client through `TryFrom`.
+#[derive(Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+
+impl Client {
+    /// Create a new `Client`. Fails with `Error::Io` if the runtime can't be created.
+    pub fn new<S: ToString>(base_url: S) -> Result<Self> {
+        Self::try_from(crate::Client::new(base_url))
+    }
+
+    /// Create a new `Client` from an existing Reqwest Client. Fails with `Error::Io` if the
+    /// runtime can't be created.
+    pub fn from_reqwest<S: ToString>(client: ReqwestClient, base_url: S) -> Result<Self> {
+        Self::try_from(crate::Client::from_reqwest(client, base_url))
+    }
+
+    /// Authorize requests through the given `AuthProvider`, replacing any previously set
+    /// authentication.
+    pub fn with_auth<P: AuthProvider + 'static>(mut self, provider: P) -> Self {
+        self.inner = self.inner.with_auth(provider);
+        self
+    }
+
+    /// Authorize requests through the given `AuthProvider` in addition to any previously set
+    /// authentication.
+    pub fn append_auth<P: AuthProvider + 'static>(mut self, provider: P) -> Self {
+        self.inner = self.inner.append_auth(provider);
+        self
+    }
+
+    /// Enable bearer authentication for the client
+    pub fn bearer_auth<S: ToString>(mut self, token: S) -> Self {
+        self.inner = self.inner.bearer_auth(token);
+        self
+    }
+
+    /// Enable basic authentication for the client
+    pub fn basic_auth<T: Into<Option<S>>, S: ToString>(mut self, user: S, pass: T) -> Self {
+        self.inner = self.inner.basic_auth(user, pass);
+        self
+    }
+
+    /// Enable query authentication for the client
+    pub fn query_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
+        self.inner = self.inner.query_auth(pairs);
+        self
+    }
+
+    /// Enable custom header authentication for the client
+    pub fn header_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
+        self.inner = self.inner.header_auth(pairs);
+        self
+    }
+
+    /// Send a single `Request`
+    pub fn send<R: Request>(&self, request: &R) -> Result<R::Response> {
+        self.runtime.block_on(self.inner.send(request))
+    }
+
+    /// Send a paginated request, returning an iterator of results
+    pub fn send_paginated<'a, R: PaginatedRequest>(
+        &'a self,
+        request: &'a R,
+    ) -> impl Iterator<Item = Result<R::Response>> + 'a {
+        let mut pages = self.inner.send_paginated(request);
+        std::iter::from_fn(move || self.runtime.block_on(pages.next()))
+    }
+}
+
+impl TryFrom<crate::Client> for Client {
+    type Error = Error;
+
+    fn try_from(inner: crate::Client) -> Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+}
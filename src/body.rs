@@ -0,0 +1,63 @@
+use crate::error::Result;
+use reqwest::Body;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+
+/// A request body streamed from an `AsyncRead` or a file, sent through `RequestData::Stream`.
+/// The body is never buffered in memory, so requests with streamed bodies can't be retried.
+pub struct BodyStream {
+    source: Source,
+}
+
+enum Source {
+    Reader(Body),
+    File(PathBuf),
+}
+
+impl BodyStream {
+    /// Stream the body from the given reader. Since `Request::data` is called again for every
+    /// page of a paginated request, a new reader should be created on each call.
+    pub fn new<R: AsyncRead + Send + Sync + 'static>(reader: R) -> Self {
+        Self {
+            source: Source::Reader(Body::wrap_stream(ReaderStream::new(reader))),
+        }
+    }
+
+    /// Stream the body from the file at `path`. The file is opened when the request is sent, and
+    /// its size is sent as the content length.
+    pub fn file<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            source: Source::File(path.into()),
+        }
+    }
+
+    /// Returns the body and its length, if known.
+    pub(crate) fn into_body(self) -> Result<(Body, Option<u64>)> {
+        match self.source {
+            Source::Reader(body) => Ok((body, None)),
+            Source::File(path) => {
+                let (body, len) = open_file(&path)?;
+                Ok((body, Some(len)))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Source::Reader(_) => f.debug_tuple("BodyStream").field(&"reader").finish(),
+            Source::File(path) => f.debug_tuple("BodyStream").field(path).finish(),
+        }
+    }
+}
+
+/// Open the file at `path` as a streaming body, returning the body and the size of the file.
+pub(crate) fn open_file(path: &Path) -> Result<(Body, u64)> {
+    let file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let stream = ReaderStream::new(tokio::fs::File::from_std(file));
+    Ok((Body::wrap_stream(stream), len))
+}
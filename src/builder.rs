@@ -0,0 +1,240 @@
+use crate::client::Client;
+use crate::error::{Error, Result};
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+use crate::tls::Identity;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Proxy, Url};
+use std::convert::TryFrom;
+use std::time::Duration;
+
+const DEFAULT_USER_AGENT: &str = concat!("vila/", env!("CARGO_PKG_VERSION"));
+
+/// A builder for configuring a `Client`.
+///
+/// Unlike the methods on `Client`, all settings are validated when the client is built, and
+/// invalid settings are returned as an `Error` instead of panicking.
+#[derive(Debug)]
+pub struct ClientBuilder {
+    base_url: String,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: String,
+    default_headers: Vec<(String, String)>,
+    auth_headers: Option<Vec<(String, String)>>,
+    proxies: Vec<String>,
+    no_proxy: bool,
+    root_certs: Vec<Vec<u8>>,
+    built_in_roots: bool,
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    identity: Option<Identity>,
+    #[cfg(feature = "rustls-tls")]
+    pins: Vec<String>,
+}
+
+impl ClientBuilder {
+    pub(crate) fn new<S: ToString>(base_url: S) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            timeout: None,
+            connect_timeout: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            default_headers: Vec::new(),
+            auth_headers: None,
+            proxies: Vec::new(),
+            no_proxy: false,
+            root_certs: Vec::new(),
+            built_in_roots: true,
+            #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+            identity: None,
+            #[cfg(feature = "rustls-tls")]
+            pins: Vec::new(),
+        }
+    }
+
+    /// Set a timeout for the whole request, from connecting until the response body has been
+    /// read. Defaults to no timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set a timeout for connecting to the server. Defaults to no timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every request. Defaults to `vila/<version>`.
+    pub fn user_agent<S: ToString>(mut self, user_agent: S) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Add a header that is sent with every request.
+    pub fn default_header<K: ToString, V: ToString>(mut self, key: K, value: V) -> Self {
+        self.default_headers
+            .push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Enable custom header authentication for the client. The header values are marked as
+    /// sensitive.
+    pub fn header_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
+        let pairs = pairs
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        self.auth_headers = Some(pairs);
+        self
+    }
+
+    /// Send all requests through the proxy at the given URL.
+    pub fn proxy<S: ToString>(mut self, url: S) -> Self {
+        self.proxies.push(url.to_string());
+        self
+    }
+
+    /// Disable all proxies, including the system proxies picked up from the environment.
+    pub fn no_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Trust the given PEM encoded root certificate, e.g. of a private CA, in addition to the
+    /// built-in root certificates.
+    pub fn add_root_certificate<C: AsRef<[u8]>>(mut self, pem: C) -> Self {
+        self.root_certs.push(pem.as_ref().to_vec());
+        self
+    }
+
+    /// Trust the built-in root certificates. Defaults to `true`. Disable this to only trust the
+    /// root certificates added through `add_root_certificate`.
+    pub fn tls_built_in_root_certs(mut self, enabled: bool) -> Self {
+        self.built_in_roots = enabled;
+        self
+    }
+
+    #[cfg(feature = "native-tls")]
+    /// Authenticate to the server with the client certificate and private key in the given
+    /// DER encoded PKCS#12 archive.
+    pub fn identity_pkcs12<C: AsRef<[u8]>, S: ToString>(mut self, der: C, password: S) -> Self {
+        self.identity = Some(Identity::Pkcs12 {
+            der: der.as_ref().to_vec(),
+            password: password.to_string(),
+        });
+        self
+    }
+
+    #[cfg(feature = "native-tls")]
+    /// Authenticate to the server with the given PEM encoded client certificate chain and PKCS#8
+    /// private key.
+    pub fn identity_pkcs8_pem<C: AsRef<[u8]>>(mut self, cert: C, key: C) -> Self {
+        self.identity = Some(Identity::Pkcs8Pem {
+            cert: cert.as_ref().to_vec(),
+            key: key.as_ref().to_vec(),
+        });
+        self
+    }
+
+    #[cfg(feature = "rustls-tls")]
+    /// Authenticate to the server with the client certificate chain and private key in the given
+    /// PEM bundle. The client will use rustls.
+    pub fn identity_pem<C: AsRef<[u8]>>(mut self, pem: C) -> Self {
+        self.identity = Some(Identity::Pem(pem.as_ref().to_vec()));
+        self
+    }
+
+    #[cfg(feature = "rustls-tls")]
+    /// Only accept server certificate chains containing a certificate with the given SHA-256
+    /// fingerprint, in hex with optional colons. Can be called multiple times to pin several
+    /// certificates, e.g. during a rotation. The chain is still verified against the trusted
+    /// root certificates. The client will use rustls, and the identity must be set through
+    /// `identity_pem`.
+    pub fn pin_certificate_sha256<S: ToString>(mut self, fingerprint: S) -> Self {
+        self.pins.push(fingerprint.to_string());
+        self
+    }
+
+    /// Build the `Client`, returning an error if any of the settings are invalid.
+    pub fn build(self) -> Result<Client> {
+        let base_url = Url::parse(&self.base_url).map_err(|e| Error::InvalidUrl {
+            msg: format!("{}: {}", self.base_url, e),
+        })?;
+        if base_url.cannot_be_a_base() || !matches!(base_url.scheme(), "http" | "https") {
+            return Err(Error::InvalidUrl {
+                msg: format!("{}: not an HTTP(S) base URL", self.base_url),
+            });
+        }
+
+        let mut builder = self
+            .tls(reqwest::Client::builder())?
+            .user_agent(self.user_agent)
+            .default_headers(header_map(self.default_headers, false)?);
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if self.no_proxy {
+            builder = builder.no_proxy();
+        }
+        for proxy in self.proxies {
+            let proxy = Proxy::all(&proxy).map_err(|e| Error::InvalidUrl {
+                msg: format!("{}: {}", proxy, e),
+            })?;
+            builder = builder.proxy(proxy);
+        }
+
+        let client = Client::from_reqwest(builder.build()?, self.base_url);
+        match self.auth_headers {
+            Some(pairs) => Ok(client.with_auth_headers(header_map(pairs, true)?)),
+            None => Ok(client),
+        }
+    }
+
+    fn tls(&self, mut builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        #[cfg(feature = "rustls-tls")]
+        if !self.pins.is_empty() {
+            let config = crate::tls::pinned_config(
+                &self.root_certs,
+                self.built_in_roots,
+                self.identity.as_ref(),
+                &self.pins,
+            )?;
+            return Ok(builder.use_preconfigured_tls(config));
+        }
+
+        for pem in &self.root_certs {
+            let cert = Certificate::from_pem(pem).map_err(|e| Error::Tls {
+                msg: format!("invalid root certificate: {}", e),
+            })?;
+            builder = builder.add_root_certificate(cert);
+        }
+        builder = builder.tls_built_in_root_certs(self.built_in_roots);
+        #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+        if let Some(identity) = &self.identity {
+            #[cfg(feature = "rustls-tls")]
+            if identity.is_rustls() {
+                builder = builder.use_rustls_tls();
+            }
+            builder = builder.identity(identity.to_reqwest()?);
+        }
+        Ok(builder)
+    }
+}
+
+fn header_map(pairs: Vec<(String, String)>, sensitive: bool) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (k, v) in pairs {
+        let name = HeaderName::try_from(&k).map_err(|_| Error::InvalidHeader {
+            msg: format!("invalid header name {:?}", k),
+        })?;
+        let mut value = HeaderValue::from_str(&v).map_err(|_| Error::InvalidHeader {
+            msg: format!("invalid value for header {}", k),
+        })?;
+        value.set_sensitive(sensitive);
+        map.append(name, value);
+    }
+    Ok(map)
+}
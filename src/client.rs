@@ -1,32 +1,43 @@
+use crate::auth::{self, AuthProvider, ClientCredentials};
+use crate::builder::ClientBuilder;
 use crate::error::{Error, Result};
+use crate::middleware::{Middleware, Next};
 use crate::pagination::{PaginatedRequest, Paginator, RequestModifier, State};
-use crate::request::{Request, RequestBuilderExt};
+use crate::path::{self, PathParams};
+use crate::rate_limit::{RateLimit, RateLimitStatus, TokenBucket};
+use crate::redact::Redactor;
+use crate::request::{Authorization, Request, RequestBuilderExt};
+use crate::retry::RetryPolicy;
+use crate::transport::Transport;
 use futures::prelude::*;
 #[cfg(feature = "progress")]
 use indicatif::{MultiProgress, ProgressBar};
-use log::debug;
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
-use reqwest::Client as ReqwestClient;
-use std::convert::TryFrom;
-use std::sync::Arc;
-
-#[derive(Clone)]
-enum Authorization {
-    Bearer(String),
-    Basic(String, Option<String>),
-    Query(Vec<(String, String)>),
-    Header(HeaderMap<HeaderValue>),
-}
+use log::{debug, log_enabled, Level};
+use reqwest::header::HeaderMap;
+use reqwest::{Client as ReqwestClient, Url};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::{timeout_at, Instant};
 
 /// The main client used for making requests.
 ///
 /// `Client` stores an async Reqwest client as well as the associated
 /// base url and possible authorization details for the REST server.
+/// Requests are sent through the Reqwest client unless a custom `Transport` is set.
 #[derive(Clone)]
 pub struct Client {
     inner: Arc<ReqwestClient>,
+    transport: Arc<dyn Transport>,
     base_url: String,
-    auth: Option<Authorization>,
+    auth: Vec<Arc<dyn AuthProvider>>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limits: Vec<(String, Arc<TokenBucket>)>,
+    rate_limit_status: Arc<Mutex<Option<RateLimitStatus>>>,
+    throttled_until: Arc<Mutex<Option<Instant>>>,
+    adaptive_throttling: bool,
+    max_throttle_delay: Duration,
+    middleware: Vec<Arc<dyn Middleware>>,
+    redactor: Redactor,
     #[cfg(feature = "progress")]
     progress: Option<Arc<MultiProgress>>,
 }
@@ -39,14 +50,30 @@ impl Client {
         Self::from_reqwest(client, base_url)
     }
 
+    /// Create a `ClientBuilder` for configuring timeouts, default headers and other settings of
+    /// the underlying HTTP client.
+    pub fn builder<S: ToString>(base_url: S) -> ClientBuilder {
+        ClientBuilder::new(base_url)
+    }
+
     /// Create a new `Client` from an existing Reqwest Client.
     pub fn from_reqwest<S: ToString>(client: ReqwestClient, base_url: S) -> Self {
+        let transport = Arc::new(client.clone());
         let inner = Arc::new(client);
 
         Self {
             inner,
+            transport,
             base_url: base_url.to_string(),
-            auth: None,
+            auth: Vec::new(),
+            retry_policy: None,
+            rate_limits: Vec::new(),
+            rate_limit_status: Default::default(),
+            throttled_until: Default::default(),
+            adaptive_throttling: false,
+            max_throttle_delay: Duration::from_secs(60),
+            middleware: Vec::new(),
+            redactor: Redactor::default(),
             #[cfg(feature = "progress")]
             progress: None,
         }
@@ -55,100 +82,381 @@ impl Client {
     #[cfg(feature = "progress")]
     /// Display a progress bar for paginated requests.
     /// If progress is shown, the URL for each request will be printed to the command line to
-    /// indicate the current request(s), so care must be taken in case the URL includes sensitive
-    /// details such as API keys.
-    pub fn show_progress(mut self) -> Self {
-        self.progress = Some(Arc::new(MultiProgress::new()));
+    /// indicate the current request(s). Secrets in the URL are redacted, see
+    /// `Client::redact_query_param`.
+    pub fn show_progress(self) -> Self {
+        self.with_progress(MultiProgress::new())
+    }
+
+    #[cfg(feature = "progress")]
+    /// Display progress bars for paginated requests in the given `MultiProgress`, e.g. to show
+    /// them alongside other progress bars or to draw them to a custom target. See
+    /// `Client::show_progress`.
+    pub fn with_progress(mut self, progress: MultiProgress) -> Self {
+        self.progress = Some(Arc::new(progress));
         self
     }
 
-    /// Enable bearer authentication for the client
-    pub fn bearer_auth<S: ToString>(mut self, token: S) -> Self {
-        self.auth = Some(Authorization::Bearer(token.to_string()));
+    /// Retry failed requests according to the given policy. Individual requests can override the
+    /// policy through `Request::retry_policy`. Without a policy, only throttled responses are
+    /// retried.
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
         self
     }
 
-    /// Enable basic authentication for the client
-    pub fn basic_auth<T: Into<Option<S>>, S: ToString>(mut self, user: S, pass: T) -> Self {
-        self.auth = Some(Authorization::Basic(
-            user.to_string(),
-            pass.into().map(|x| x.to_string()),
-        ));
+    /// Limit the rate of requests sent by the client. The limit is shared by all clones of the
+    /// client.
+    pub fn rate_limit(self, limit: RateLimit) -> Self {
+        self.rate_limit_endpoint("", limit)
+    }
+
+    /// Limit the rate of requests sent to endpoints starting with `prefix`, e.g. `/users`. Each
+    /// request is only counted against the limit with the longest matching prefix, so different
+    /// resources can be given separate budgets. The limit is shared by all clones of the client.
+    pub fn rate_limit_endpoint<S: ToString>(mut self, prefix: S, limit: RateLimit) -> Self {
+        let prefix = prefix.to_string().trim_matches('/').to_string();
+        let bucket = Arc::new(TokenBucket::new(limit));
+        self.rate_limits.retain(|(p, _)| *p != prefix);
+        self.rate_limits.push((prefix, bucket));
         self
     }
 
-    /// Enable query authentication for the client
-    pub fn query_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
-        let pairs = pairs
-            .into_iter()
-            .map(|(k, v)| (k.to_string(), v.to_string()))
-            .collect();
-        self.auth = Some(Authorization::Query(pairs));
+    /// Slow down requests based on the rate limit headers returned by the server. Once less than a
+    /// tenth of the quota is left, requests are spread out evenly over the rest of the rate limit
+    /// window, and no requests are sent after the quota runs out until the window resets.
+    /// Concurrent requests, including requests sent through clones of the client, queue up behind
+    /// each other rather than being sent at once.
+    pub fn adaptive_throttling(mut self) -> Self {
+        self.adaptive_throttling = true;
         self
     }
 
-    /// Enable custom header authentication for the client
-    pub fn header_auth<S: ToString>(mut self, pairs: Vec<(S, S)>) -> Self {
-        let mut map = HeaderMap::new();
-        for (k, v) in pairs {
-            let k = k.to_string();
-            let v = v.to_string();
-            let mut header_value = HeaderValue::from_str(&v).expect("Failed to create HeaderValue");
-            header_value.set_sensitive(true);
-            map.insert(
-                HeaderName::try_from(&k).expect("Failed to create HeaderName"),
-                header_value,
-            );
-        }
-        self.auth = Some(Authorization::Header(map));
+    /// The longest time a request is held back by adaptive throttling, regardless of the reset
+    /// time reported by the server. Defaults to 60 seconds.
+    pub fn max_throttle_delay(mut self, max: Duration) -> Self {
+        self.max_throttle_delay = max;
+        self
+    }
+
+    /// The rate limit quota reported by the server in the latest response, if any. The status is
+    /// shared by all clones of the client.
+    pub fn rate_limit_status(&self) -> Option<RateLimitStatus> {
+        *self
+            .rate_limit_status
+            .lock()
+            .expect("Rate limit status lock poisoned")
+    }
+
+    /// Send requests through a custom transport instead of the Reqwest client.
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Arc::new(transport);
+        self
+    }
+
+    /// Add a middleware to the client. Middleware runs in the order it was added.
+    pub fn with_middleware<M: Middleware + 'static>(mut self, middleware: M) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Authorize requests through the given `AuthProvider`, replacing any previously set
+    /// authentication.
+    pub fn with_auth<P: AuthProvider + 'static>(mut self, provider: P) -> Self {
+        self.auth = vec![Arc::new(provider)];
         self
     }
 
-    fn format_request<R: Request>(&self, request: &R) -> Result<reqwest::Request> {
+    /// Authorize requests through the given `AuthProvider` in addition to any previously set
+    /// authentication, e.g. to send both an API key and a bearer token. Providers are applied in
+    /// the order they were added, so providers that sign the request should be added last.
+    pub fn append_auth<P: AuthProvider + 'static>(mut self, provider: P) -> Self {
+        self.auth.push(Arc::new(provider));
+        self
+    }
+
+    /// Redact the given query parameter wherever the client logs or displays a request. Query
+    /// parameters added by query authentication are always redacted.
+    pub fn redact_query_param<S: ToString>(mut self, name: S) -> Self {
+        self.redactor.query_param(&name.to_string());
+        self
+    }
+
+    /// Redact the given header wherever the client logs a request. Header values marked as
+    /// sensitive, such as those added by the built-in authentication, are always redacted.
+    pub fn redact_header<S: ToString>(mut self, name: S) -> Self {
+        self.redactor.header(&name.to_string());
+        self
+    }
+
+    /// Enable bearer authentication for the client, replacing any previously set authentication
+    pub fn bearer_auth<S: ToString>(self, token: S) -> Self {
+        self.with_auth(auth::Bearer::new(token))
+    }
+
+    /// Enable basic authentication for the client, replacing any previously set authentication
+    pub fn basic_auth<T: Into<Option<S>>, S: ToString>(self, user: S, pass: T) -> Self {
+        self.with_auth(auth::Basic::new(user, pass))
+    }
+
+    /// Enable query authentication for the client, replacing any previously set authentication
+    pub fn query_auth<S: ToString>(self, pairs: Vec<(S, S)>) -> Self {
+        self.with_auth(auth::Query::new(pairs))
+    }
+
+    /// Enable custom header authentication for the client, replacing any previously set
+    /// authentication
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the header names or values are invalid. Use `ClientBuilder::header_auth`
+    /// to get an error instead.
+    pub fn header_auth<S: ToString>(self, pairs: Vec<(S, S)>) -> Self {
+        self.with_auth(auth::Header::new(pairs))
+    }
+
+    /// Enable OAuth2 authentication for the client, using the client credentials flow. Replaces
+    /// any previously set authentication. Tokens are fetched through the Reqwest client of this
+    /// client, unless another one is set through `ClientCredentials::with_reqwest`.
+    pub fn oauth2_auth(self, credentials: ClientCredentials) -> Self {
+        let credentials = credentials.default_reqwest(ReqwestClient::clone(&self.inner));
+        self.with_auth(credentials)
+    }
+
+    pub(crate) fn with_auth_headers(self, headers: HeaderMap) -> Self {
+        self.with_auth(auth::Header::from(headers))
+    }
+
+    fn url(&self, endpoint: &str) -> Result<Url> {
+        let invalid = |e: url::ParseError| Error::InvalidUrl {
+            msg: format!("{} joined with {}: {}", self.base_url, endpoint, e),
+        };
+        // Absolute endpoints, e.g. "next" links returned by the server, are used as-is
+        if let Ok(url) = Url::parse(endpoint) {
+            if matches!(url.scheme(), "http" | "https") {
+                return Ok(url);
+            }
+        }
+        let mut base = Url::parse(&self.base_url).map_err(invalid)?;
+        // Make sure the last segment of the base path is kept when joining
+        if !base.path().ends_with('/') {
+            base.set_path(&format!("{}/", base.path()));
+        }
+        // Join as a relative path, so that a colon in the first segment isn't taken as a scheme
+        let url = base
+            .join(&format!("./{}", endpoint.trim_matches('/')))
+            .map_err(invalid)?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(Error::InvalidUrl {
+                msg: format!("{} is not an http(s) URL", url),
+            });
+        }
+        Ok(url)
+    }
+
+    fn format_request<R: Request>(
+        &self,
+        request: &R,
+        path_params: PathParams,
+    ) -> Result<reqwest::Request> {
         let endpoint = request.endpoint();
-        let endpoint = endpoint.trim_matches('/');
-        let url = format!("{}/{}", self.base_url, endpoint);
+        let url = self.url(&path::expand(&endpoint, &path_params)?)?;
 
-        let req = self
+        let mut req = self
             .inner
             .request(R::METHOD, url)
             .headers(request.headers())
-            .request_data(request.data());
-
-        let req = match &self.auth {
-            None => req,
-            Some(Authorization::Bearer(token)) => req.bearer_auth(token),
-            Some(Authorization::Basic(user, pass)) => req.basic_auth(user, pass.as_ref()),
-            Some(Authorization::Query(pairs)) => req.query(&pairs),
-            Some(Authorization::Header(pairs)) => req.headers(pairs.clone()),
-        };
+            .query(&request.query())
+            .request_data(request.data())?;
+        if let Some(timeout) = request.timeout() {
+            req = req.timeout(timeout);
+        }
         req.build().map_err(From::from)
     }
 
-    fn send_raw<R>(&self, req: reqwest::Request) -> impl Future<Output = Result<R>>
-    where
-        R: for<'de> serde::Deserialize<'de>,
-    {
-        debug!("Sending request: {:?}", req);
-        self.inner
-            .execute(req)
-            .map_err(From::from)
-            .and_then(|res| async {
-                let status = res.status();
-                if status.is_success() {
-                    res.json().await.map_err(From::from)
-                } else if status.is_client_error() {
-                    Err(Error::ClientError(status, res.text().await.unwrap()))
-                } else {
-                    Err(Error::ServerError(status, res.text().await.unwrap()))
+    fn providers<'a>(
+        &'a self,
+        authorization: &'a Authorization,
+        url: &Url,
+    ) -> &'a [Arc<dyn AuthProvider>] {
+        match authorization {
+            // Keep the credentials of the client from leaking to other hosts through absolute URLs
+            Authorization::Inherit if !self.is_same_origin(url) => &[],
+            Authorization::Inherit => &self.auth,
+            Authorization::None => &[],
+            Authorization::Use(providers) => providers,
+        }
+    }
+
+    fn is_same_origin(&self, url: &Url) -> bool {
+        Url::parse(&self.base_url).is_ok_and(|base| base.origin() == url.origin())
+    }
+
+    fn rate_limiter<R: Request>(&self, request: &R) -> Option<&TokenBucket> {
+        let endpoint = request.endpoint();
+        let endpoint = endpoint.trim_matches('/');
+        self.rate_limits
+            .iter()
+            .filter(|(prefix, _)| {
+                prefix.is_empty()
+                    || endpoint == prefix
+                    || endpoint
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, bucket)| bucket.as_ref())
+    }
+
+    async fn throttle(&self) {
+        let until = {
+            let mut status = self
+                .rate_limit_status
+                .lock()
+                .expect("Rate limit status lock poisoned");
+            let delay = status.and_then(|s| s.throttle_delay());
+            let exhausted = status.is_some_and(|s| s.remaining == Some(0));
+            // Count the request against the quota until the server reports an updated status
+            if let Some(remaining) = status.as_mut().and_then(|s| s.remaining.as_mut()) {
+                *remaining = remaining.saturating_sub(1);
+            }
+            delay.map(|delay| {
+                let now = Instant::now();
+                let mut throttled_until = self
+                    .throttled_until
+                    .lock()
+                    .expect("Rate limit status lock poisoned");
+                // Queue up behind requests that are already waiting, so that concurrent requests
+                // are spread out as well. Once the quota is exhausted, all requests wait for the
+                // window to reset.
+                let until = match *throttled_until {
+                    Some(previous) if previous > now && !exhausted => previous + delay,
+                    _ => now + delay,
                 }
+                .min(now + self.max_throttle_delay);
+                *throttled_until = Some(until);
+                until
             })
+        };
+        if let Some(until) = until {
+            debug!(
+                "Rate limit quota running low, waiting {:?}",
+                until.saturating_duration_since(Instant::now())
+            );
+            tokio::time::sleep_until(until).await;
+        }
+    }
+
+    /// Send a request, applying rate limits, authorization and the retry policy. `on_send` is
+    /// called with the redacted URL of every attempt that is sent.
+    async fn execute<R: Request>(
+        &self,
+        request: &R,
+        mut req: reqwest::Request,
+        on_send: Option<&(dyn Fn(&str) + Sync)>,
+    ) -> Result<reqwest::Response> {
+        let (policy, retry_errors) = match request.retry_policy().or(self.retry_policy) {
+            Some(policy) => (policy, true),
+            None => (RetryPolicy::throttled(), false),
+        };
+        let rate_limiter = self.rate_limiter(request);
+        let mut attempt = 0;
+        let mut reauthorized = false;
+        let authorization = request.authorization();
+        let providers = self.providers(&authorization, req.url());
+        loop {
+            if let Some(rate_limiter) = rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            if self.adaptive_throttling {
+                self.throttle().await;
+            }
+            // Bodies that can't be cloned (e.g. streams) can only be sent once
+            let next_req = req.try_clone();
+            let authorized = authorize(providers, req).await?;
+            if log_enabled!(Level::Debug) {
+                debug!(
+                    "Sending request: {}",
+                    self.redactor.request(&authorized, providers)
+                );
+            }
+            if let Some(on_send) = on_send {
+                on_send(&self.redactor.url(authorized.url(), providers));
+            }
+            let sent = without_body(&authorized);
+            let result = Next::new(self.transport.as_ref(), &self.middleware)
+                .run(authorized)
+                .await;
+            if let Ok(res) = &result {
+                if let Some(status) = RateLimitStatus::from_headers(res.headers()) {
+                    *self
+                        .rate_limit_status
+                        .lock()
+                        .expect("Rate limit status lock poisoned") = Some(status);
+                }
+            }
+            // Let the providers refresh their credentials, e.g. after an access token was rejected
+            if let Ok(res) = &result {
+                let mut retry = false;
+                for provider in providers {
+                    retry |= provider.on_response(&sent, res.status()).await;
+                }
+                if retry && !reauthorized {
+                    if let Some(next_req) = next_req {
+                        reauthorized = true;
+                        req = next_req;
+                        continue;
+                    }
+                }
+            }
+            let next_req = match next_req {
+                Some(next_req) if attempt < policy.max_retries => next_req,
+                _ => return result,
+            };
+            let delay = match &result {
+                Ok(res) => policy.retry_delay(res, attempt),
+                Err(Error::Reqwest(e)) if retry_errors && (e.is_connect() || e.is_timeout()) => {
+                    Some(policy.backoff(attempt))
+                }
+                Err(_) => None,
+            };
+            let delay = match delay {
+                Some(delay) => delay,
+                None => return result,
+            };
+            debug!(
+                "Retrying request in {:?} (attempt {} of {})",
+                delay,
+                attempt + 1,
+                policy.max_retries
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+            req = next_req;
+        }
+    }
+
+    async fn send_raw<R: Request>(
+        &self,
+        request: &R,
+        req: reqwest::Request,
+        on_send: Option<&(dyn Fn(&str) + Sync)>,
+    ) -> Result<R::Response> {
+        let res = self.execute(request, req, on_send).await?;
+        let status = res.status();
+        if status.is_success() {
+            res.json().await.map_err(From::from)
+        } else if status.is_client_error() {
+            Err(Error::ClientError(status, res.text().await.unwrap()))
+        } else {
+            Err(Error::ServerError(status, res.text().await.unwrap()))
+        }
     }
 
     /// Send a single `Request`
     pub async fn send<R: Request>(&self, request: &R) -> Result<R::Response> {
-        let req = self.format_request(request)?;
-        self.send_raw(req).await
+        let req = self.format_request(request, request.path_params())?;
+        self.send_raw(request, req, None).await
     }
 
     /// Send a paginated request, returning a stream of results
@@ -165,17 +473,20 @@ impl Client {
             (
                 request.paginator(),
                 State::Start(request.initial_page()),
+                None,
                 #[cfg(feature = "progress")]
                 progress,
             ),
             move |x| async move {
                 #[cfg(feature = "progress")]
-                let (paginator, state, progress) = x;
+                let (paginator, state, deadline, progress) = x;
 
                 #[cfg(not(feature = "progress"))]
-                let (paginator, state) = x;
+                let (paginator, state, deadline) = x;
+
+                // The deadline starts when the first page is requested
+                let deadline = deadline.or_else(|| request.deadline().map(|d| Instant::now() + d));
 
-                let mut base_request = self.format_request(request)?;
                 let page = match state {
                     State::Start(None) => None,
                     State::Start(Some(ref page)) | State::Next(ref page) => Some(page),
@@ -188,15 +499,32 @@ impl Client {
                         return Ok(None);
                     }
                 };
-                if let Some(page) = page {
-                    let modifier = paginator.modifier(page.clone());
+                let modifier = page.map(|page| paginator.modifier(page.clone()));
+                let mut path_params = request.path_params();
+                if let Some(modifier) = &modifier {
+                    path_params = path_params.merge(modifier.path_params());
+                }
+                let mut base_request = self.format_request(request, path_params)?;
+                if let Some(modifier) = &modifier {
                     modifier.modify_request(&mut base_request)?;
                 }
+                // Show the URL that is actually sent, including any authorization
                 #[cfg(feature = "progress")]
-                if let Some(p) = progress.as_ref() {
-                    p.set_message(base_request.url().to_string())
-                }
-                let response = self.send_raw(base_request).await?;
+                let set_message = progress
+                    .as_ref()
+                    .map(|p| move |url: &str| p.set_message(url.to_string()));
+                #[cfg(feature = "progress")]
+                let on_send = set_message.as_ref().map(|f| f as &(dyn Fn(&str) + Sync));
+                #[cfg(not(feature = "progress"))]
+                let on_send = None;
+                let response = match deadline {
+                    Some(deadline) => {
+                        timeout_at(deadline, self.send_raw(request, base_request, on_send))
+                            .await
+                            .map_err(|_| Error::DeadlineExceeded)??
+                    }
+                    None => self.send_raw(request, base_request, on_send).await?,
+                };
                 let state = paginator.next(page, &response);
                 #[cfg(feature = "progress")]
                 if let Some(ref p) = progress {
@@ -207,6 +535,7 @@ impl Client {
                     (
                         paginator,
                         state,
+                        deadline,
                         #[cfg(feature = "progress")]
                         progress,
                     ),
@@ -215,3 +544,22 @@ impl Client {
         ))
     }
 }
+
+/// Copy the method, URL and headers of a request, for passing to `AuthProvider::on_response`.
+fn without_body(req: &reqwest::Request) -> reqwest::Request {
+    let mut copy = reqwest::Request::new(req.method().clone(), req.url().clone());
+    *copy.headers_mut() = req.headers().clone();
+    copy
+}
+
+/// Apply the authorization to a formatted request. This happens right before the request is sent,
+/// so that any pagination changes are already in place.
+async fn authorize(
+    providers: &[Arc<dyn AuthProvider>],
+    mut req: reqwest::Request,
+) -> Result<reqwest::Request> {
+    for provider in providers {
+        provider.authorize(&mut req).await?;
+    }
+    Ok(req)
+}
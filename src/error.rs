@@ -4,7 +4,7 @@ use thiserror::Error;
 /// Possible errors in the request-response lifecycle.
 pub enum Error {
     #[error("Reqwest error: {0}")]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(#[source] reqwest::Error),
 
     #[error("Serde error: {error}\nMsg: {msg}")]
     Serde {
@@ -21,8 +21,33 @@ pub enum Error {
     #[error("Server error. Received status {0}. Message: {1}")]
     ServerError(reqwest::StatusCode, String),
 
+    #[error("Authorization error: {msg}")]
+    Authorization { msg: String },
+
+    #[error("Deadline exceeded")]
+    DeadlineExceeded,
+
+    #[error("Invalid URL: {msg}")]
+    InvalidUrl { msg: String },
+
+    #[error("Missing path parameter: {name}")]
+    MissingPathParam { name: String },
+
+    #[error("Invalid header: {msg}")]
+    InvalidHeader { msg: String },
+
+    #[error("TLS error: {msg}")]
+    Tls { msg: String },
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        // The URL may contain secrets, e.g. the parameters added by query authentication
+        Error::Reqwest(e.without_url())
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
@@ -2,14 +2,59 @@
 //! for authentication, various request and response types and pagination.
 //!
 //! Originally inspired by [ring-api](https://github.com/H2CO3/ring_api)
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod body;
+mod builder;
 mod client;
 mod error;
+mod middleware;
+pub mod multipart;
 pub mod pagination;
+mod path;
+mod rate_limit;
+mod redact;
 mod request;
+mod retry;
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+mod tls;
+mod transport;
 
+pub use auth::{AuthProvider, ClientCredentials};
+pub use body::BodyStream;
+pub use builder::ClientBuilder;
 pub use client::Client;
-pub use error::Error;
+pub use error::{Error, Result};
+pub use middleware::{Middleware, Next};
+pub use multipart::Multipart;
+pub use path::PathParams;
+pub use rate_limit::{RateLimit, RateLimitStatus};
 pub use request::*;
 pub use reqwest::header;
 pub use reqwest::Method;
 pub use reqwest::StatusCode;
+pub use retry::RetryPolicy;
+pub use transport::Transport;
+#[cfg(feature = "derive")]
+pub use vila_derive::Request;
+
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    //! Helpers for the code generated by `vila-derive`, not part of the public API.
+    use log::warn;
+    use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+    use std::fmt::Display;
+
+    /// Insert a header, skipping it if the value isn't a valid header value.
+    pub fn insert_header<V: Display>(headers: &mut HeaderMap, name: &'static str, value: V) {
+        match HeaderValue::from_str(&value.to_string()) {
+            Ok(value) => {
+                headers.insert(HeaderName::from_static(name), value);
+            }
+            // The value itself isn't logged, as it may be a secret
+            Err(_) => warn!("Skipping header {} with an invalid value", name),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::error::Result;
+use crate::transport::Transport;
+use futures::future::BoxFuture;
+use reqwest::{Request as RawRequest, Response};
+use std::sync::Arc;
+
+/// Trait for intercepting requests sent by the client.
+///
+/// Middleware is called with the fully formatted outgoing request, right before it's sent to the
+/// server, and returns the response before it's decoded. Middleware registered on the client
+/// through `Client::with_middleware` runs in the order it was added, for every request, every
+/// retry and every page of a paginated request.
+///
+/// Middleware can modify the request before passing it on to `next`, inspect or replace the
+/// response returned by `next`, or short-circuit the request by returning a response without
+/// calling `next` at all. Responses can be created from an `http::Response`.
+pub trait Middleware: Send + Sync {
+    /// Handle the request, calling `next.run(request)` to pass it on to the rest of the chain.
+    fn handle<'a>(&'a self, request: RawRequest, next: Next<'a>)
+        -> BoxFuture<'a, Result<Response>>;
+}
+
+/// The remaining middleware chain for a request.
+pub struct Next<'a> {
+    transport: &'a dyn Transport,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(transport: &'a dyn Transport, middleware: &'a [Arc<dyn Middleware>]) -> Self {
+        Self {
+            transport,
+            middleware,
+        }
+    }
+
+    /// Pass the request on to the next middleware, or send it through the transport if this is the
+    /// end of the chain.
+    pub fn run(self, request: RawRequest) -> BoxFuture<'a, Result<Response>> {
+        match self.middleware.split_first() {
+            Some((middleware, rest)) => middleware.handle(request, Next::new(self.transport, rest)),
+            None => self.transport.execute(request),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Multipart form data for file uploads.
+use crate::body::open_file;
+use crate::error::{Error, Result};
+use rand::Rng;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::{multipart, RequestBuilder};
+use std::path::PathBuf;
+
+/// A multipart form, sent through `RequestData::Multipart`.
+///
+/// Forms with only text and byte parts are encoded up front, so the request can be retried and
+/// signed like any other request. Forms with file parts are streamed from disk, which means they
+/// are sent only once and can't be signed by `HmacAuth` or `SigV4Auth`.
+#[derive(Debug, Default)]
+pub struct Multipart {
+    parts: Vec<(String, Part)>,
+}
+
+impl Multipart {
+    /// Create a new, empty `Multipart` form.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a text field.
+    pub fn text<N: ToString, V: ToString>(self, name: N, value: V) -> Self {
+        self.part(name, Part::text(value))
+    }
+
+    /// Add a part with the given bytes.
+    pub fn bytes<N: ToString, B: Into<Vec<u8>>>(self, name: N, bytes: B) -> Self {
+        self.part(name, Part::bytes(bytes))
+    }
+
+    /// Add a part streamed from the file at `path`. The file name defaults to the name of the
+    /// file on disk.
+    pub fn file<N: ToString, P: Into<PathBuf>>(self, name: N, path: P) -> Self {
+        self.part(name, Part::file(path))
+    }
+
+    /// Add a custom part, e.g. with a file name or content type.
+    pub fn part<N: ToString>(mut self, name: N, part: Part) -> Self {
+        self.parts.push((name.to_string(), part));
+        self
+    }
+
+    /// Set the form as the body of the request.
+    pub(crate) fn apply(self, builder: RequestBuilder) -> Result<RequestBuilder> {
+        if self.parts.iter().any(|(_, part)| part.is_file()) {
+            return Ok(builder.multipart(self.into_form()?));
+        }
+        let mut rng = rand::thread_rng();
+        let boundary = format!(
+            "{:016x}-{:016x}-{:016x}-{:016x}",
+            rng.gen::<u64>(),
+            rng.gen::<u64>(),
+            rng.gen::<u64>(),
+            rng.gen::<u64>()
+        );
+        let mut body = Vec::new();
+        for (name, part) in self.parts {
+            part.encode(&name, &boundary, &mut body)?;
+        }
+        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+        let mut headers = HeaderMap::new();
+        let content_type = format!("multipart/form-data; boundary={}", boundary);
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_str(&content_type).expect("boundary is a valid header value"),
+        );
+        Ok(builder.headers(headers).body(body))
+    }
+
+    fn into_form(self) -> Result<multipart::Form> {
+        self.parts
+            .into_iter()
+            .try_fold(multipart::Form::new(), |form, (name, part)| {
+                Ok(form.part(name, part.into_part()?))
+            })
+    }
+}
+
+/// A single part of a `Multipart` form.
+#[derive(Debug)]
+pub struct Part {
+    data: Data,
+    file_name: Option<String>,
+    content_type: Option<String>,
+}
+
+#[derive(Debug)]
+enum Data {
+    Text(String),
+    Bytes(Vec<u8>),
+    File(PathBuf),
+}
+
+impl Part {
+    fn new(data: Data) -> Self {
+        Self {
+            data,
+            file_name: None,
+            content_type: None,
+        }
+    }
+
+    /// A text part.
+    pub fn text<V: ToString>(value: V) -> Self {
+        Self::new(Data::Text(value.to_string()))
+    }
+
+    /// A part with the given bytes.
+    pub fn bytes<B: Into<Vec<u8>>>(bytes: B) -> Self {
+        Self::new(Data::Bytes(bytes.into()))
+    }
+
+    /// A part streamed from the file at `path`. The file is opened when the request is sent, and
+    /// the file name defaults to the name of the file on disk. Requests with file parts can't be
+    /// retried or signed, see `Multipart`.
+    pub fn file<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new(Data::File(path.into()))
+    }
+
+    /// Set the file name of the part.
+    pub fn file_name<S: ToString>(mut self, file_name: S) -> Self {
+        self.file_name = Some(file_name.to_string());
+        self
+    }
+
+    /// Set the content type of the part, e.g. `image/png`.
+    pub fn content_type<S: ToString>(mut self, content_type: S) -> Self {
+        self.content_type = Some(content_type.to_string());
+        self
+    }
+
+    fn is_file(&self) -> bool {
+        matches!(self.data, Data::File(_))
+    }
+
+    /// Write the part to an in-memory form body.
+    fn encode(self, name: &str, boundary: &str, body: &mut Vec<u8>) -> Result<()> {
+        let data = match self.data {
+            Data::Text(value) => value.into_bytes(),
+            Data::Bytes(bytes) => bytes,
+            Data::File(_) => unreachable!("forms with file parts are streamed"),
+        };
+        let mut headers = format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"",
+            boundary,
+            escape(name)
+        );
+        if let Some(file_name) = &self.file_name {
+            headers.push_str(&format!("; filename=\"{}\"", escape(file_name)));
+        }
+        if let Some(content_type) = &self.content_type {
+            HeaderValue::from_str(content_type).map_err(|_| Error::InvalidHeader {
+                msg: format!("invalid content type {:?}", content_type),
+            })?;
+            headers.push_str(&format!("\r\nContent-Type: {}", content_type));
+        }
+        headers.push_str("\r\n\r\n");
+        body.extend_from_slice(headers.as_bytes());
+        body.extend_from_slice(&data);
+        body.extend_from_slice(b"\r\n");
+        Ok(())
+    }
+
+    fn into_part(self) -> Result<multipart::Part> {
+        let mut file_name = self.file_name;
+        let part = match self.data {
+            Data::Text(value) => multipart::Part::text(value),
+            Data::Bytes(bytes) => multipart::Part::bytes(bytes),
+            Data::File(path) => {
+                let (body, len) = open_file(&path)?;
+                if file_name.is_none() {
+                    file_name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned());
+                }
+                multipart::Part::stream_with_length(body, len)
+            }
+        };
+        let part = match file_name {
+            Some(file_name) => part.file_name(file_name),
+            None => part,
+        };
+        match self.content_type {
+            Some(content_type) => part.mime_str(&content_type).map_err(From::from),
+            None => Ok(part),
+        }
+    }
+}
+
+/// Escape a name or file name for a quoted `Content-Disposition` parameter.
+fn escape(value: &str) -> String {
+    value
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
@@ -1,13 +1,23 @@
 //! Constructs for wrapping a paginated API.
 use crate::error::{Error, Result};
+use crate::path::PathParams;
 use crate::Request;
 use reqwest::Request as RawRequest;
 use std::collections::HashMap;
+use std::time::Duration;
+#[cfg(feature = "derive")]
+pub use vila_derive::PaginatedRequest;
 
 /// Trait for updating an HTTP request with pagination data.
 pub trait RequestModifier {
     /// Modify the request with updated pagination data.
     fn modify_request(&self, request: &mut RawRequest) -> Result<()>;
+
+    /// Path parameters that replace those given by `Request::path_params` when the endpoint
+    /// template is expanded for the next page. Defaults to none.
+    fn path_params(&self) -> PathParams {
+        Default::default()
+    }
 }
 
 /// Base trait for paginators. Paginators can use the previous pagination state
@@ -38,6 +48,13 @@ pub trait PaginatedRequest: Request {
     fn initial_page(&self) -> Option<<Self as PaginatedRequest>::Data> {
         None
     }
+
+    /// The total time budget for fetching all pages, counted from when the first page is
+    /// requested, i.e. when the stream is first polled. Once the budget is spent, the stream ends
+    /// with `Error::DeadlineExceeded`. Defaults to `None`, which means there is no deadline.
+    fn deadline(&self) -> Option<Duration> {
+        None
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -124,8 +141,11 @@ pub mod query {
 pub mod path {
     //! Constructs for working with APIs that implement paging through one or more path parameters.
     use super::*;
-    #[derive(Debug, Clone)]
+    use crate::path::encode_segment;
+
+    use std::marker::PhantomData;
 
+    #[derive(Debug, Clone)]
     /// A modifier that updates the path portion of a request's URL. This modifier holds a HashMap
     /// that maps the position of a path parameter to its updated value.
     pub struct PathModifier {
@@ -135,52 +155,83 @@ pub mod path {
     impl RequestModifier for PathModifier {
         fn modify_request(&self, request: &mut RawRequest) -> Result<()> {
             let url = request.url_mut();
-            let temp_url = url.clone();
-            let mut new_segments: Vec<&str> = temp_url
+            // Existing segments are already encoded, so only the new values are encoded
+            let mut new_segments: Vec<String> = url
                 .path_segments()
                 .ok_or_else(|| Error::Pagination {
                     msg: "URL cannot be a base".to_string(),
                 })?
                 .enumerate()
-                .map(|(i, x)| self.data.get(&i).map(|val| val.as_str()).unwrap_or(x))
-                .collect();
+                .map(|(i, x)| match self.data.get(&i) {
+                    Some(val) => encode_segment(val),
+                    None => Ok(x.to_string()),
+                })
+                .collect::<Result<_>>()?;
             let len = new_segments.len();
             // Append any additional path segments not present in original path
-            new_segments.extend(self.data.iter().filter_map(|(i, x)| {
-                if *i >= len {
-                    Some(x.as_str())
-                } else {
-                    None
-                }
-            }));
-            let mut path_segments = url.path_segments_mut().map_err(|_| Error::Pagination {
-                msg: "URL cannot be a base".to_string(),
-            })?;
-            path_segments.clear();
-            path_segments.extend(new_segments.iter());
+            let mut additional: Vec<_> = self.data.iter().filter(|(i, _)| **i >= len).collect();
+            additional.sort_by_key(|(i, _)| **i);
+            for (_, x) in additional {
+                new_segments.push(encode_segment(x)?);
+            }
+            url.set_path(&new_segments.join("/"));
             Ok(())
         }
     }
 
+    #[derive(Debug, Clone)]
+    /// A modifier that updates parameters of the endpoint template, see `Request::path_params`.
+    /// This modifier holds a HashMap that maps the name of a parameter to its updated value.
+    pub struct PathParamModifier {
+        pub data: HashMap<String, String>,
+    }
+
+    impl RequestModifier for PathParamModifier {
+        fn modify_request(&self, _request: &mut RawRequest) -> Result<()> {
+            // The parameters are bound when the endpoint template is expanded
+            Ok(())
+        }
+
+        fn path_params(&self) -> PathParams {
+            self.data.iter().collect()
+        }
+    }
+
     /// A paginator that implements pagination through one or more path parameters. The closure inside
     /// the paginator should return the path segment number and the new path segment, e.g. (2, "foo")
-    /// represents changing the third path segment to "foo"
-    pub struct PathPaginator<T, U> {
+    /// represents changing the third path segment to "foo". To update parameters of the endpoint
+    /// template by name instead, create it with `with_modifier` and `PathParamModifier`.
+    pub struct PathPaginator<T, U, M = PathModifier> {
         #[allow(clippy::type_complexity)]
         f: Box<dyn 'static + Send + Sync + Fn(Option<&U>, &T) -> Option<U>>,
+        modifier: PhantomData<fn() -> M>,
     }
 
-    impl<T, U> PathPaginator<T, U> {
+    impl<T, U> PathPaginator<T, U, PathModifier> {
         pub fn new<F: 'static + Send + Sync + Fn(Option<&U>, &T) -> Option<U>>(f: F) -> Self {
-            Self { f: Box::new(f) }
+            Self::with_modifier(f)
+        }
+    }
+
+    impl<T, U, M> PathPaginator<T, U, M> {
+        /// Creates a paginator that modifies the request with a custom modifier, such as
+        /// `PathParamModifier`, rather than `PathModifier`.
+        pub fn with_modifier<F: 'static + Send + Sync + Fn(Option<&U>, &T) -> Option<U>>(
+            f: F,
+        ) -> Self {
+            Self {
+                f: Box::new(f),
+                modifier: PhantomData,
+            }
         }
     }
 
-    impl<T, U> Paginator<T, U> for PathPaginator<T, U>
+    impl<T, U, M> Paginator<T, U> for PathPaginator<T, U, M>
     where
-        U: Into<PathModifier>,
+        U: Into<M>,
+        M: RequestModifier,
     {
-        type Modifier = PathModifier;
+        type Modifier = M;
         fn modifier(&self, data: U) -> Self::Modifier {
             data.into()
         }
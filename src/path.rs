@@ -0,0 +1,105 @@
+use crate::error::{Error, Result};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::iter::FromIterator;
+
+/// Characters that are kept as-is in a path segment, following RFC 3986.
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Values for the `{name}` parameters of an endpoint template, e.g. `/users/{id}/posts/{post_id}`.
+/// See `Request::path_params`.
+#[derive(Clone, Debug, Default)]
+pub struct PathParams {
+    params: HashMap<String, String>,
+}
+
+impl PathParams {
+    /// Create a new, empty set of path parameters.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Bind the parameter `name` to the given value, replacing any previous value. The value is
+    /// percent-encoded as a single path segment, so it may contain slashes, spaces or unicode.
+    pub fn param<N: ToString, V: Display>(mut self, name: N, value: V) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Returns the value bound to the parameter `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(|v| v.as_str())
+    }
+
+    /// Bind all parameters in `other`, replacing any parameters with the same name.
+    pub(crate) fn merge(mut self, other: PathParams) -> Self {
+        self.params.extend(other.params);
+        self
+    }
+}
+
+impl<N: ToString, V: Display> FromIterator<(N, V)> for PathParams {
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(PathParams::new(), |params, (name, value)| {
+                params.param(name, value)
+            })
+    }
+}
+
+/// Percent-encode a value as a single path segment.
+pub(crate) fn encode_segment(value: &str) -> Result<String> {
+    // Dot segments would be removed when the URL is parsed, even if encoded
+    if value == "." || value == ".." {
+        return Err(Error::InvalidUrl {
+            msg: format!("{:?} is not a valid path segment", value),
+        });
+    }
+    Ok(utf8_percent_encode(value, SEGMENT).to_string())
+}
+
+/// Replace the `{name}` parameters in an endpoint template with their encoded values. Braces that
+/// don't enclose a valid parameter name are kept as-is.
+pub(crate) fn expand<'a>(template: &'a str, params: &PathParams) -> Result<Cow<'a, str>> {
+    if !template.contains('{') {
+        return Ok(template.into());
+    }
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let name = after
+            .find('}')
+            .map(|end| &after[..end])
+            .filter(|name| is_param_name(name));
+        match name {
+            Some(name) => {
+                let value = params.get(name).ok_or_else(|| Error::MissingPathParam {
+                    name: name.to_string(),
+                })?;
+                expanded.push_str(&encode_segment(value)?);
+                rest = &after[name.len() + 1..];
+            }
+            None => {
+                expanded.push('{');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded.into())
+}
+
+fn is_param_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
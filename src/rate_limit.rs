@@ -0,0 +1,190 @@
+use log::debug;
+use reqwest::header::HeaderMap;
+use std::convert::TryFrom;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Rate limit windows that reset further in the future than this are treated as unknown.
+const MAX_RESET: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Clone, Copy, Debug)]
+/// A client-side rate limit, enforced through a token bucket.
+///
+/// The bucket holds up to `burst` tokens and is refilled at a constant rate. Every request
+/// (including retries and every page of a paginated request) consumes one token, and waits for
+/// the bucket to refill if it's empty.
+pub struct RateLimit {
+    requests: u32,
+    per: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Allow `requests` requests per `per`, with a burst size of `requests`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` or `per` is zero.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        assert!(requests > 0, "rate limit must allow at least one request");
+        assert!(!per.is_zero(), "rate limit period must be non-zero");
+        Self {
+            requests,
+            per,
+            burst: requests,
+        }
+    }
+
+    /// Allow `requests` requests per second, with a burst size of `requests`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `requests` is zero.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    /// Set the maximum number of requests that can be sent at once after a period of inactivity.
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        f64::from(self.requests) / self.per.as_secs_f64()
+    }
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<BucketState>,
+}
+
+impl TokenBucket {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(BucketState {
+                tokens: f64::from(limit.burst),
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token from the bucket, waiting until one is available.
+    pub(crate) async fn acquire(&self) {
+        let wait = {
+            let mut state = self.state.lock().expect("Rate limiter lock poisoned");
+            let now = Instant::now();
+            let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+            state.tokens = (state.tokens + elapsed * self.limit.tokens_per_second())
+                .min(f64::from(self.limit.burst));
+            state.last_refill = now;
+            // Reserve the token up front so concurrent callers queue up behind each other
+            state.tokens -= 1.0;
+            if state.tokens >= 0.0 {
+                return;
+            }
+            Duration::from_secs_f64(-state.tokens / self.limit.tokens_per_second())
+        };
+        debug!("Rate limit reached, waiting {:?}", wait);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+/// The rate limit quota reported by the server in its latest response.
+///
+/// Both the `X-RateLimit-Limit`, `X-RateLimit-Remaining` and `X-RateLimit-Reset` headers and the
+/// IETF `RateLimit-*` and `RateLimit` headers are supported.
+pub struct RateLimitStatus {
+    /// The number of requests allowed in the current window.
+    pub limit: Option<u64>,
+    /// The number of requests left in the current window.
+    pub remaining: Option<u64>,
+    /// The time at which the current window resets, or `None` if it is unknown or implausibly far
+    /// in the future.
+    pub reset: Option<SystemTime>,
+}
+
+impl RateLimitStatus {
+    /// Parses the rate limit headers of a response, returning `None` if there are none.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let mut status = Self::default();
+        for prefix in &["x-ratelimit-", "ratelimit-"] {
+            let get = |name: &str| {
+                headers
+                    .get(format!("{}{}", prefix, name))
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(parse_leading_int)
+            };
+            status.limit = status.limit.or_else(|| get("limit"));
+            status.remaining = status.remaining.or_else(|| get("remaining"));
+            status.reset = status.reset.or_else(|| get("reset").and_then(reset_time));
+        }
+        if let Some(value) = headers.get("ratelimit").and_then(|v| v.to_str().ok()) {
+            // Either `limit=100, remaining=50, reset=30` or `"policy";r=50;t=30`
+            for (key, value) in value
+                .split([',', ';'])
+                .filter_map(|param| param.split_once('='))
+            {
+                let value = match parse_leading_int(value) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match key.trim() {
+                    "limit" => status.limit = status.limit.or(Some(value)),
+                    "remaining" | "r" => status.remaining = status.remaining.or(Some(value)),
+                    "reset" | "t" => status.reset = status.reset.or_else(|| reset_time(value)),
+                    _ => {}
+                }
+            }
+        }
+        Some(status).filter(|s| *s != Self::default())
+    }
+
+    /// The time to wait before sending the next request, pacing requests evenly over the rest of
+    /// the window once less than a tenth of the quota is left.
+    pub(crate) fn throttle_delay(&self) -> Option<Duration> {
+        let remaining = self.remaining?;
+        let until_reset = self.reset?.duration_since(SystemTime::now()).ok()?;
+        if remaining == 0 {
+            return Some(until_reset);
+        }
+        match self.limit {
+            Some(limit) if remaining <= limit / 10 => {
+                let remaining = u32::try_from(remaining).unwrap_or(u32::MAX);
+                Some(until_reset / remaining.saturating_add(1))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn parse_leading_int(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// Returns the time at which the rate limit window resets, or `None` if the value is implausible,
+/// e.g. a timestamp in milliseconds.
+fn reset_time(value: u64) -> Option<SystemTime> {
+    let now = SystemTime::now();
+    // Some APIs send the reset as a unix timestamp rather than the number of seconds left
+    let reset = if value > 1_000_000_000 {
+        UNIX_EPOCH.checked_add(Duration::from_secs(value))?
+    } else {
+        now + Duration::from_secs(value)
+    };
+    Some(reset).filter(|reset| *reset <= now + MAX_RESET)
+}
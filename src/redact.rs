@@ -0,0 +1,77 @@
+use crate::auth::AuthProvider;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Request as RawRequest, Url};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const REDACTED: &str = "***";
+
+/// Hides secrets in requests that are logged or displayed by the client.
+///
+/// Sensitive header values, passwords in URLs, query parameters added by query authentication and
+/// any query parameters or headers configured on the client are replaced with `***`.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Redactor {
+    query_params: HashSet<String>,
+    headers: HashSet<String>,
+}
+
+impl Redactor {
+    pub(crate) fn query_param(&mut self, name: &str) {
+        self.query_params.insert(name.to_lowercase());
+    }
+
+    pub(crate) fn header(&mut self, name: &str) {
+        self.headers.insert(name.to_lowercase());
+    }
+
+    pub(crate) fn url(&self, url: &Url, providers: &[Arc<dyn AuthProvider>]) -> String {
+        let mut url = url.clone();
+        if url.password().is_some() {
+            let _ = url.set_password(Some(REDACTED));
+        }
+        if url.query().is_none() {
+            return url.to_string();
+        }
+
+        let provided: HashSet<String> = providers
+            .iter()
+            .flat_map(|p| p.sensitive_query_params())
+            .map(|name| name.to_lowercase())
+            .collect();
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| {
+                let name = k.to_lowercase();
+                if self.query_params.contains(&name) || provided.contains(&name) {
+                    (k.into_owned(), REDACTED.to_string())
+                } else {
+                    (k.into_owned(), v.into_owned())
+                }
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+        url.to_string()
+    }
+
+    pub(crate) fn request(
+        &self,
+        request: &RawRequest,
+        providers: &[Arc<dyn AuthProvider>],
+    ) -> String {
+        let mut headers = HeaderMap::new();
+        for (name, value) in request.headers() {
+            if value.is_sensitive() || self.headers.contains(name.as_str()) {
+                headers.append(name, HeaderValue::from_static(REDACTED));
+            } else {
+                headers.append(name, value.clone());
+            }
+        }
+        format!(
+            "{} {} {:?}",
+            request.method(),
+            self.url(request.url(), providers),
+            headers
+        )
+    }
+}
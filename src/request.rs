@@ -1,10 +1,21 @@
-use reqwest::{header::HeaderMap, Method, RequestBuilder};
+use crate::auth::AuthProvider;
+use crate::body::BodyStream;
+use crate::error::{Error, Result};
+use crate::multipart::Multipart;
+use crate::path::PathParams;
+use crate::RetryPolicy;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use reqwest::{Method, RequestBuilder};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::borrow::Cow;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Additional data to be sent along with the request.
+#[derive(Default)]
 pub enum RequestData<T> {
     /// No additional data.
+    #[default]
     Empty,
     /// HTTP form data.
     Form(T),
@@ -12,11 +23,40 @@ pub enum RequestData<T> {
     Json(T),
     /// Query data.
     Query(T),
+    /// Multipart form data, e.g. for file uploads. Forms with file parts are streamed, so the
+    /// request can't be retried or signed, see `Multipart`.
+    Multipart(Multipart),
+    /// Raw bytes, sent as-is, e.g. a pre-encoded or compressed payload. Any content type or
+    /// encoding should be set through `Request::headers`.
+    Bytes(Vec<u8>),
+    /// Text sent as-is, with the given content type, e.g. `application/x-ndjson`.
+    Text {
+        /// The body of the request.
+        body: String,
+        /// The content type of the body.
+        content_type: String,
+    },
+    /// A body streamed from a reader or file, e.g. for large uploads that shouldn't be buffered
+    /// in memory.
+    Stream(BodyStream),
 }
 
-impl<T> Default for RequestData<T> {
-    fn default() -> Self {
-        RequestData::Empty
+/// The authorization used for a request.
+#[derive(Clone, Default)]
+pub enum Authorization {
+    /// Use the authentication set on the client.
+    #[default]
+    Inherit,
+    /// Send the request without any authentication.
+    None,
+    /// Use the given providers instead of the authentication set on the client.
+    Use(Vec<Arc<dyn AuthProvider>>),
+}
+
+impl Authorization {
+    /// Use the given provider instead of the authentication set on the client.
+    pub fn using<P: AuthProvider + 'static>(provider: P) -> Self {
+        Self::Use(vec![Arc::new(provider)])
     }
 }
 
@@ -32,11 +72,22 @@ pub trait Request {
     const METHOD: Method = Method::GET;
 
     /// The endpoint to which the request will be sent. The base url is set in the client, and the
-    /// endpoint method returns the specific resource endpoint.
-    fn endpoint(&self) -> Cow<str>;
+    /// endpoint method returns the specific resource endpoint. The endpoint can be a template with
+    /// `{name}` parameters, e.g. `/users/{id}`, which are bound through `Request::path_params`.
+    /// Absolute URLs are used as-is, but the authentication of the client is only applied when the
+    /// URL has the same origin as the base url.
+    fn endpoint(&self) -> Cow<'_, str>;
+
+    /// The values for the parameters in the endpoint template. Each value is percent-encoded as a
+    /// single path segment, and sending the request fails with `Error::MissingPathParam` if a
+    /// parameter in the template has no value.
+    fn path_params(&self) -> PathParams {
+        Default::default()
+    }
 
     /// Any additional headers that should be sent with the request. Note that common headers such
-    /// as authorization headers should be set on the client directly.
+    /// as authorization headers should be set on the client directly, or through
+    /// `Request::authorization`.
     fn headers(&self) -> HeaderMap {
         Default::default()
     }
@@ -45,13 +96,38 @@ pub trait Request {
     fn data(&self) -> RequestData<&Self::Data> {
         Default::default()
     }
+
+    /// Query parameters sent with the request, in addition to any request data. This allows
+    /// sending query parameters together with a JSON or form body. Pagination modifiers replace
+    /// parameters with the same name.
+    fn query(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        Vec::new()
+    }
+
+    /// The timeout for the request, overriding any timeout set on the client. The timeout applies
+    /// to each attempt separately when the request is retried.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The retry policy for the request. Defaults to `None`, which means the retry policy of the
+    /// client will be used.
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        None
+    }
+
+    /// The authorization for the request. Defaults to `Authorization::Inherit`, which means the
+    /// authentication of the client will be used.
+    fn authorization(&self) -> Authorization {
+        Default::default()
+    }
 }
 
 #[derive(Debug)]
 /// Struct symbolizing an empty response from the server.
 pub struct EmptyResponse;
 impl<'de> Deserialize<'de> for EmptyResponse {
-    fn deserialize<D>(_deserializer: D) -> Result<EmptyResponse, D::Error>
+    fn deserialize<D>(_deserializer: D) -> std::result::Result<EmptyResponse, D::Error>
     where
         D: Deserializer<'de>,
     {
@@ -60,16 +136,35 @@ impl<'de> Deserialize<'de> for EmptyResponse {
 }
 
 pub(crate) trait RequestBuilderExt: Sized {
-    fn request_data<T: Serialize>(self, body: RequestData<T>) -> Self;
+    fn request_data<T: Serialize>(self, body: RequestData<T>) -> Result<Self>;
 }
 
 impl RequestBuilderExt for RequestBuilder {
-    fn request_data<T: Serialize>(self, body: RequestData<T>) -> Self {
-        match body {
+    fn request_data<T: Serialize>(self, body: RequestData<T>) -> Result<Self> {
+        Ok(match body {
             RequestData::Empty => self,
             RequestData::Form(value) => self.form(&value),
             RequestData::Json(value) => self.json(&value),
             RequestData::Query(value) => self.query(&value),
-        }
+            RequestData::Multipart(form) => form.apply(self)?,
+            RequestData::Bytes(bytes) => self.body(bytes),
+            RequestData::Text { body, content_type } => {
+                let mut headers = HeaderMap::new();
+                let value =
+                    HeaderValue::from_str(&content_type).map_err(|_| Error::InvalidHeader {
+                        msg: format!("invalid content type {:?}", content_type),
+                    })?;
+                headers.insert(CONTENT_TYPE, value);
+                self.headers(headers).body(body)
+            }
+            RequestData::Stream(stream) => {
+                let (body, len) = stream.into_body()?;
+                let mut headers = HeaderMap::new();
+                if let Some(len) = len {
+                    headers.insert(CONTENT_LENGTH, HeaderValue::from(len));
+                }
+                self.headers(headers).body(body)
+            }
+        })
     }
 }
@@ -1,12 +1,33 @@
-use std::time::Duration;
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Response, StatusCode};
+use std::time::{Duration, SystemTime};
 
 #[derive(Clone, Copy, Debug)]
+/// Policy describing if and how failed requests should be retried.
+///
+/// A policy can be set for all requests on the client through `Client::with_retry_policy`, or
+/// for a single request type through `Request::retry_policy`. Connection errors and timeouts are
+/// always retried, while error responses are retried depending on their status class.
+///
+/// Throttled responses (429, or 503 with a `Retry-After` header) are always retried, even when no
+/// policy is set, in which case the default number of retries and delays are used. If the response
+/// includes a `Retry-After` header, the client waits for the requested time instead of the regular
+/// backoff, capped to `max_retry_after`.
 pub struct RetryPolicy {
+    /// The maximum number of retries after the initial attempt.
     pub max_retries: u64,
+    /// Whether to randomize the backoff delay to avoid synchronized retries across clients.
     pub jitter: bool,
+    /// Whether to retry requests that receive a 4xx response.
     pub retry_on_client_error: bool,
+    /// Whether to retry requests that receive a 5xx response.
     pub retry_on_server_error: bool,
-    pub timeout: Duration,
+    /// The delay before the first retry. The delay doubles for every subsequent retry.
+    pub initial_backoff: Duration,
+    /// The longest `Retry-After` delay the client is willing to wait. Responses asking for a
+    /// longer delay are retried after `max_retry_after`, earlier than the server asked for.
+    pub max_retry_after: Duration,
 }
 
 impl Default for RetryPolicy {
@@ -16,7 +37,67 @@ impl Default for RetryPolicy {
             jitter: true,
             retry_on_client_error: false,
             retry_on_server_error: true,
-            timeout: Duration::from_secs(1),
+            initial_backoff: Duration::from_secs(1),
+            max_retry_after: Duration::from_secs(60),
         }
     }
 }
+
+impl RetryPolicy {
+    /// The policy used when neither the client nor the request set one, which only retries
+    /// throttled responses.
+    pub(crate) fn throttled() -> Self {
+        Self {
+            retry_on_server_error: false,
+            ..Default::default()
+        }
+    }
+
+    fn should_retry(&self, status: StatusCode) -> bool {
+        (status.is_client_error() && self.retry_on_client_error)
+            || (status.is_server_error() && self.retry_on_server_error)
+    }
+
+    /// Returns the delay before retrying a request that received `res`, or `None` if the request
+    /// should not be retried.
+    pub(crate) fn retry_delay(&self, res: &Response, attempt: u64) -> Option<Duration> {
+        let status = res.status();
+        match retry_after(res.headers()) {
+            Some(delay)
+                if status == StatusCode::TOO_MANY_REQUESTS
+                    || status == StatusCode::SERVICE_UNAVAILABLE =>
+            {
+                Some(delay.min(self.max_retry_after))
+            }
+            _ if status == StatusCode::TOO_MANY_REQUESTS || self.should_retry(status) => {
+                Some(self.backoff(attempt))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) fn backoff(&self, attempt: u64) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.min(u64::from(u32::MAX)) as u32);
+        let delay = self.initial_backoff.saturating_mul(factor);
+        if self.jitter {
+            // Keep at least half of the delay so retries are still spaced out
+            let half = delay / 2;
+            half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Parses a `Retry-After` header, given either in delta-seconds or as an HTTP-date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = httpdate::parse_http_date(value).ok()?;
+    Some(
+        date.duration_since(SystemTime::now())
+            .unwrap_or(Duration::ZERO),
+    )
+}
@@ -0,0 +1,184 @@
+use crate::error::{Error, Result};
+use std::fmt;
+
+/// A client certificate and private key used for mutual TLS.
+pub(crate) enum Identity {
+    #[cfg(feature = "native-tls")]
+    Pkcs12 { der: Vec<u8>, password: String },
+    #[cfg(feature = "native-tls")]
+    Pkcs8Pem { cert: Vec<u8>, key: Vec<u8> },
+    #[cfg(feature = "rustls-tls")]
+    Pem(Vec<u8>),
+}
+
+impl Identity {
+    pub(crate) fn to_reqwest(&self) -> Result<reqwest::Identity> {
+        let identity = match self {
+            #[cfg(feature = "native-tls")]
+            Identity::Pkcs12 { der, password } => reqwest::Identity::from_pkcs12_der(der, password),
+            #[cfg(feature = "native-tls")]
+            Identity::Pkcs8Pem { cert, key } => reqwest::Identity::from_pkcs8_pem(cert, key),
+            #[cfg(feature = "rustls-tls")]
+            Identity::Pem(pem) => reqwest::Identity::from_pem(pem),
+        };
+        identity.map_err(|e| Error::Tls {
+            msg: format!("invalid identity: {}", e),
+        })
+    }
+
+    #[cfg(feature = "rustls-tls")]
+    /// Whether the identity can only be used with rustls, rather than the native TLS backend.
+    pub(crate) fn is_rustls(&self) -> bool {
+        match self {
+            #[cfg(feature = "native-tls")]
+            Identity::Pkcs12 { .. } | Identity::Pkcs8Pem { .. } => false,
+            #[cfg(feature = "rustls-tls")]
+            Identity::Pem(_) => true,
+        }
+    }
+}
+
+// Keep the private key out of debug output
+impl fmt::Debug for Identity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            #[cfg(feature = "native-tls")]
+            Identity::Pkcs12 { .. } => "Pkcs12",
+            #[cfg(feature = "native-tls")]
+            Identity::Pkcs8Pem { .. } => "Pkcs8Pem",
+            #[cfg(feature = "rustls-tls")]
+            Identity::Pem(_) => "Pem",
+        };
+        f.debug_tuple("Identity").field(&kind).finish()
+    }
+}
+
+#[cfg(feature = "rustls-tls")]
+pub(crate) use pinning::pinned_config;
+
+#[cfg(feature = "rustls-tls")]
+mod pinning {
+    use super::Identity;
+    use crate::error::{Error, Result};
+    use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+    use rustls::{Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore};
+    use rustls::{Error as TlsError, ServerName};
+    use sha2::{Digest, Sha256};
+    use std::sync::Arc;
+    use std::time::SystemTime;
+
+    /// Verifies the server certificate as usual, and additionally requires one of the
+    /// certificates in the chain to match a pinned SHA-256 fingerprint.
+    struct PinnedVerifier {
+        inner: WebPkiVerifier,
+        pins: Vec<Vec<u8>>,
+    }
+
+    impl ServerCertVerifier for PinnedVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &Certificate,
+            intermediates: &[Certificate],
+            server_name: &ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: SystemTime,
+        ) -> std::result::Result<ServerCertVerified, TlsError> {
+            let verified = self.inner.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                scts,
+                ocsp_response,
+                now,
+            )?;
+            let pinned = std::iter::once(end_entity)
+                .chain(intermediates)
+                .any(|cert| {
+                    let fingerprint = Sha256::digest(&cert.0);
+                    self.pins.iter().any(|pin| pin[..] == fingerprint[..])
+                });
+            if pinned {
+                Ok(verified)
+            } else {
+                Err(TlsError::General(
+                    "server certificate doesn't match any pinned certificate".to_string(),
+                ))
+            }
+        }
+    }
+
+    /// Builds a rustls configuration for a client with pinned certificates.
+    pub(crate) fn pinned_config(
+        root_certs: &[Vec<u8>],
+        built_in_roots: bool,
+        identity: Option<&Identity>,
+        pins: &[String],
+    ) -> Result<ClientConfig> {
+        let mut roots = RootCertStore::empty();
+        if built_in_roots {
+            roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|ta| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    ta.subject,
+                    ta.spki,
+                    ta.name_constraints,
+                )
+            }));
+        }
+        for pem in root_certs {
+            for cert in certs(pem)? {
+                roots.add(&cert).map_err(tls_error)?;
+            }
+        }
+        let pins = pins
+            .iter()
+            .map(|pin| {
+                hex::decode(pin.replace(':', ""))
+                    .ok()
+                    .filter(|pin| pin.len() == 32)
+                    .ok_or_else(|| Error::Tls {
+                        msg: format!("invalid SHA-256 fingerprint {:?}", pin),
+                    })
+            })
+            .collect::<Result<_>>()?;
+        let verifier = PinnedVerifier {
+            inner: WebPkiVerifier::new(roots, None),
+            pins,
+        };
+
+        let builder = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(verifier));
+        match identity {
+            None => Ok(builder.with_no_client_auth()),
+            Some(Identity::Pem(pem)) => builder
+                .with_client_auth_cert(certs(pem)?, private_key(pem)?)
+                .map_err(tls_error),
+            #[cfg(feature = "native-tls")]
+            Some(_) => Err(Error::Tls {
+                msg: "pinned certificates require a PEM identity".to_string(),
+            }),
+        }
+    }
+
+    fn certs(pem: &[u8]) -> Result<Vec<Certificate>> {
+        let certs = rustls_pemfile::certs(&mut &pem[..]).map_err(tls_error)?;
+        Ok(certs.into_iter().map(Certificate).collect())
+    }
+
+    fn private_key(pem: &[u8]) -> Result<PrivateKey> {
+        use rustls_pemfile::Item;
+        for item in rustls_pemfile::read_all(&mut &pem[..]).map_err(tls_error)? {
+            if let Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) = item {
+                return Ok(PrivateKey(key));
+            }
+        }
+        Err(Error::Tls {
+            msg: "no private key found in identity".to_string(),
+        })
+    }
+
+    fn tls_error<E: std::fmt::Display>(e: E) -> Error {
+        Error::Tls { msg: e.to_string() }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::error::Result;
+use futures::future::BoxFuture;
+use futures::prelude::*;
+use reqwest::{Client as ReqwestClient, Request as RawRequest, Response};
+
+/// Trait for the underlying transport that sends requests to the server.
+///
+/// By default, requests are sent through a Reqwest `Client`. A custom transport can be set
+/// through `Client::with_transport`, e.g. to serve responses from memory in unit tests or to send
+/// requests over a different kind of connection. Responses can be created from an
+/// `http::Response`.
+pub trait Transport: Send + Sync {
+    /// Send the request, returning the response from the server.
+    fn execute(&self, request: RawRequest) -> BoxFuture<'_, Result<Response>>;
+}
+
+impl Transport for ReqwestClient {
+    fn execute(&self, request: RawRequest) -> BoxFuture<'_, Result<Response>> {
+        ReqwestClient::execute(self, request)
+            .map_err(From::from)
+            .boxed()
+    }
+}
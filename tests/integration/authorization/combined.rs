@@ -0,0 +1,66 @@
+use crate::utils::EmptyHello;
+use vila::auth::{Basic, Bearer, Header, Query};
+use vila::Client;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn query_and_bearer_auth() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri())
+        .query_auth(vec![("api_key", "KEY")])
+        .append_auth(Bearer::new("TOKEN"));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(query_param("api_key", "KEY"))
+        .and(header("Authorization", "Bearer TOKEN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn basic_and_header_auth() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri())
+        .with_auth(Basic::new("user", "pass"))
+        .append_auth(Header::new(vec![("X-Tenant", "acme")]));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Basic dXNlcjpwYXNz"))
+        .and(header("X-Tenant", "acme"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn with_auth_replaces_previous_auth() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri())
+        .bearer_auth("OLD")
+        .append_auth(Query::new(vec![("api_key", "KEY")]))
+        .with_auth(Bearer::new("NEW"));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer NEW"))
+        .and(crate::utils::matchers::MissingQuery::new("api_key"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
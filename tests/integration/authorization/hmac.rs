@@ -0,0 +1,125 @@
+use crate::utils::{EmptyHello, JsonHello, NameGreeting, PagedHello, QueryHello};
+use futures::prelude::*;
+use serde_json::json;
+use vila::auth::hmac::{Algorithm, Encoding, HmacAuth};
+use vila::{Client, Error};
+use wiremock::matchers::{header, header_exists, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn hmac_auth() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let auth = HmacAuth::new("secret").template("{method} {path} {query} {body}");
+    let client = Client::new(server.uri()).with_auth(auth);
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(query_param("name", "world"))
+        .and(header(
+            "X-Signature",
+            "f7951c8065734d7017d7fba248f960575bc9295be4c261b20e524334482a1a23",
+        ))
+        .and(header_exists("X-Timestamp"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"message": "Hi"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let req = QueryHello {
+        name: "world".into(),
+    };
+    let res = client.send(&req).await.unwrap();
+    assert_eq!(
+        res,
+        NameGreeting {
+            message: "Hi".into()
+        }
+    );
+}
+
+#[tokio::test]
+async fn hmac_auth_signs_body_with_custom_settings() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let auth = HmacAuth::new("secret")
+        .template("{method} {path} {body}")
+        .algorithm(Algorithm::Sha512)
+        .encoding(Encoding::Base64)
+        .signature_header("X-Api-Sign")
+        .timestamp_header("X-Api-Time")
+        .nonce_header("X-Api-Nonce")
+        .key("X-Api-Key", "key-id")
+        .timestamp_millis();
+    let client = Client::new(server.uri()).with_auth(auth);
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header(
+            "X-Api-Sign",
+            "l1BY2t6xY39cagAS7G0zgw9YtLmJpLk/5BIB0FGXA4HNqRQjY10ySP3TMSmV1alSZki04fHDMuBInk4uhZP1lw==",
+        ))
+        .and(header_exists("X-Api-Time"))
+        .and(header_exists("X-Api-Nonce"))
+        .and(header("X-Api-Key", "key-id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"message": "Hi"})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let req = JsonHello {
+        name: "world".into(),
+    };
+    client.send(&req).await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let timestamp = requests[0].headers.get(&"X-Api-Time".into()).unwrap();
+    // Milliseconds since the epoch have 13 digits until the year 2286
+    assert_eq!(timestamp.as_str().len(), 13);
+}
+
+#[tokio::test]
+async fn hmac_auth_signs_final_paginated_url() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let auth = HmacAuth::new("secret").template("{method}\n{path}\n{query}\n{body}");
+    let client = Client::new(server.uri()).with_auth(auth);
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(header(
+            "X-Signature",
+            "899d4d2b230da429bf1cf7e96f426c01c1065e8bd159750bcaaa70450b72e86c",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"next_page": 1})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(query_param("page", "1"))
+        .and(header(
+            "X-Signature",
+            "7c039e5baa2087b2607acf3ba8e061e85afbd3426f4717a8d2c133e5ab32124a",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"next_page": null})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pages: Vec<_> = client.send_paginated(&PagedHello).collect().await;
+    assert_eq!(pages.len(), 2);
+    assert!(pages.iter().all(|p| p.is_ok()));
+}
+
+#[tokio::test]
+async fn hmac_auth_rejects_unknown_placeholders() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let auth = HmacAuth::new("secret").template("{method}{host}");
+    let client = Client::new(server.uri()).with_auth(auth);
+
+    let res = client.send(&EmptyHello).await;
+    assert!(matches!(res, Err(Error::Authorization { .. })));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -1,4 +1,11 @@
 mod basic;
 mod bearer;
+mod combined;
 mod header;
+mod hmac;
+mod oauth2;
+mod per_request;
+mod pool;
+mod provider;
 mod query;
+mod sigv4;
@@ -0,0 +1,194 @@
+use crate::utils::EmptyHello;
+use serde_json::json;
+use std::time::Duration;
+use vila::auth::ClientCredentials;
+use vila::{Client, Error};
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn token(access_token: &str, expires_in: u64) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "access_token": access_token,
+        "token_type": "Bearer",
+        "expires_in": expires_in,
+    }))
+}
+
+fn credentials(server: &MockServer) -> ClientCredentials {
+    ClientCredentials::new(format!("{}/token", server.uri()), "id", "secret")
+}
+
+#[tokio::test]
+async fn oauth2_auth() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).oauth2_auth(credentials(&server).scope("read"));
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(header("Authorization", "Basic aWQ6c2VjcmV0")) // id:secret in base64
+        .and(body_string_contains("grant_type=client_credentials"))
+        .and(body_string_contains("scope=read"))
+        .respond_with(token("TOKEN", 3600))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer TOKEN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    client.send(&EmptyHello).await.unwrap();
+    // The token is shared between clones
+    client.clone().send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn oauth2_credentials_in_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).oauth2_auth(credentials(&server).credentials_in_body());
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(body_string_contains("client_id=id"))
+        .and(body_string_contains("client_secret=secret"))
+        .respond_with(token("TOKEN", 3600))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer TOKEN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn oauth2_refreshes_expiring_token() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client =
+        Client::new(&uri).oauth2_auth(credentials(&server).refresh_margin(Duration::from_secs(60)));
+
+    // Expires within the refresh margin, so it's refreshed before every request
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token("TOKEN", 30))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn oauth2_refreshes_token_on_unauthorized() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).oauth2_auth(credentials(&server));
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token("REVOKED", 3600))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(token("TOKEN", 3600))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer REVOKED"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer TOKEN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn oauth2_token_request_fails() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).oauth2_auth(credentials(&server));
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .respond_with(ResponseTemplate::new(400).set_body_string("invalid_client"))
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client.send(&EmptyHello).await.unwrap_err(),
+        Error::Authorization { msg } if msg.contains("invalid_client")
+    ));
+}
+
+#[tokio::test]
+async fn oauth2_uses_client_settings() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri)
+        .default_header("X-Tenant", "vila")
+        .build()
+        .unwrap()
+        .oauth2_auth(credentials(&server));
+
+    Mock::given(method("POST"))
+        .and(path("/token"))
+        .and(header("X-Tenant", "vila"))
+        .respond_with(token("TOKEN", 3600))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer TOKEN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
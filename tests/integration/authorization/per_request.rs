@@ -0,0 +1,70 @@
+use crate::utils::matchers::MissingHeader;
+use crate::utils::EmptyHello;
+use std::borrow::Cow;
+use vila::auth::Bearer;
+use vila::{Authorization, Client, EmptyResponse, Request};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct AdminHello;
+
+impl Request for AdminHello {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/admin".into()
+    }
+
+    fn authorization(&self) -> Authorization {
+        Authorization::using(Bearer::new("ADMIN"))
+    }
+}
+
+struct PublicHello;
+
+impl Request for PublicHello {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/public".into()
+    }
+
+    fn authorization(&self) -> Authorization {
+        Authorization::None
+    }
+}
+
+#[tokio::test]
+async fn per_request_authorization() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri()).bearer_auth("USER");
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer USER"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/admin"))
+        .and(header("Authorization", "Bearer ADMIN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/public"))
+        .and(MissingHeader::new("Authorization"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    client.send(&AdminHello).await.unwrap();
+    client.send(&PublicHello).await.unwrap();
+}
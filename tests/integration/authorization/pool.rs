@@ -0,0 +1,90 @@
+use crate::utils::{EmptyHello, PagedHello};
+use futures::prelude::*;
+use serde_json::json;
+use vila::auth::pool::{CredentialPool, Rotation};
+use vila::Client;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn pool_rotates_across_clones() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri()).with_auth(CredentialPool::bearer(vec!["A", "B"]));
+    let clone = client.clone();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer A"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer B"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    clone.send(&EmptyHello).await.unwrap();
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn pool_rotates_across_pages() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let pool = CredentialPool::query("api_key", vec!["k1", "k2"]);
+    let client = Client::new(server.uri()).with_auth(pool);
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(query_param("api_key", "k1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"next_page": 1})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(query_param("page", "1"))
+        .and(query_param("api_key", "k2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"next_page": null})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pages: Vec<_> = client.send_paginated(&PagedHello).collect().await;
+    assert_eq!(pages.len(), 2);
+    assert!(pages.iter().all(|p| p.is_ok()));
+}
+
+#[tokio::test]
+async fn pool_benches_throttled_credentials() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let pool = CredentialPool::bearer(vec!["A", "B"]).rotation(Rotation::LeastRecentlyThrottled);
+    let client = Client::new(server.uri()).with_auth(pool);
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer A"))
+        .respond_with(ResponseTemplate::new(429))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer B"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    // The first request is retried with B, which is then used until A is back from the bench
+    for _ in 0..3 {
+        client.send(&EmptyHello).await.unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+use crate::utils::{EmptyHello, PagedHello};
+use futures::future::BoxFuture;
+use futures::prelude::*;
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vila::auth::AuthProvider;
+use vila::header::HeaderValue;
+use vila::{Client, Result, StatusCode};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Signs the final URL of every request, including pagination parameters.
+struct UrlSigner;
+
+impl AuthProvider for UrlSigner {
+    fn authorize<'a>(&'a self, request: &'a mut reqwest::Request) -> BoxFuture<'a, Result<()>> {
+        async move {
+            tokio::task::yield_now().await;
+            let signature = format!("signed:{}", request.url().query().unwrap_or(""));
+            request
+                .headers_mut()
+                .insert("X-Signature", HeaderValue::from_str(&signature).unwrap());
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
+/// Hands out a new token for every request, and asks for a retry when a token is rejected.
+#[derive(Default)]
+struct Rotating {
+    issued: AtomicUsize,
+}
+
+impl AuthProvider for Rotating {
+    fn authorize<'a>(&'a self, request: &'a mut reqwest::Request) -> BoxFuture<'a, Result<()>> {
+        let token = self.issued.fetch_add(1, Ordering::SeqCst);
+        let value = HeaderValue::from_str(&format!("Bearer {}", token)).unwrap();
+        request.headers_mut().insert("Authorization", value);
+        future::ok(()).boxed()
+    }
+
+    fn on_response<'a>(
+        &'a self,
+        _request: &'a reqwest::Request,
+        status: StatusCode,
+    ) -> BoxFuture<'a, bool> {
+        future::ready(status == StatusCode::UNAUTHORIZED).boxed()
+    }
+}
+
+#[tokio::test]
+async fn provider_authorizes_every_page() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri()).with_auth(UrlSigner);
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(header("X-Signature", "signed:"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"next_page": 1})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(query_param("page", "1"))
+        .and(header("X-Signature", "signed:page=1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"next_page": null})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pages: Vec<_> = client.send_paginated(&PagedHello).collect().await;
+    assert_eq!(pages.len(), 2);
+    assert!(pages.iter().all(|p| p.is_ok()));
+}
+
+#[tokio::test]
+async fn provider_retries_rejected_credentials_once() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri()).with_auth(Rotating::default());
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer 0"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("Authorization", "Bearer 1"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let res = client.send(&EmptyHello).await;
+    assert!(matches!(
+        res,
+        Err(vila::Error::ClientError(StatusCode::UNAUTHORIZED, _))
+    ));
+}
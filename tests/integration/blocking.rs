@@ -0,0 +1,91 @@
+use crate::utils::{NameGreeting, PagedHello, QueryHello};
+use serde_json::json;
+use std::convert::TryFrom;
+use vila::blocking::Client;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn send() {
+    let _ = env_logger::try_init();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start());
+    let client = Client::new(server.uri()).unwrap().bearer_auth("TOKEN");
+
+    runtime.block_on(
+        Mock::given(method("GET"))
+            .and(path("/hello"))
+            .and(query_param("name", "world"))
+            .and(header("Authorization", "Bearer TOKEN"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"message": "Hello, world!"})),
+            )
+            .mount(&server),
+    );
+
+    let response = client
+        .send(&QueryHello {
+            name: "world".into(),
+        })
+        .unwrap();
+    assert_eq!(
+        response,
+        NameGreeting {
+            message: "Hello, world!".into()
+        }
+    );
+}
+
+#[test]
+fn send_paginated() {
+    let _ = env_logger::try_init();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start());
+    let client = Client::new(server.uri()).unwrap();
+
+    runtime.block_on(async {
+        Mock::given(method("GET"))
+            .and(path("/page"))
+            .and(query_param("page", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "next_page": null })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/page"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "next_page": 1 })))
+            .mount(&server)
+            .await;
+    });
+
+    let pages: Vec<_> = client.send_paginated(&PagedHello).collect();
+    assert_eq!(pages.len(), 2);
+    assert!(pages.iter().all(|page| page.is_ok()));
+}
+
+#[test]
+fn from_async_client() {
+    let _ = env_logger::try_init();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start());
+    let client =
+        Client::try_from(vila::Client::new(server.uri()).header_auth(vec![("X-Key", "KEY")]))
+            .unwrap();
+
+    runtime.block_on(
+        Mock::given(method("GET"))
+            .and(path("/hello"))
+            .and(header("X-Key", "KEY"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"message": "Hello, world!"})),
+            )
+            .expect(1)
+            .mount(&server),
+    );
+
+    client
+        .send(&QueryHello {
+            name: "world".into(),
+        })
+        .unwrap();
+}
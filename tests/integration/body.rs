@@ -0,0 +1,220 @@
+use crate::utils::matchers::MissingQuery;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use vila::header::{HeaderMap, HeaderValue, CONTENT_ENCODING};
+use vila::pagination::query::*;
+use vila::pagination::*;
+use vila::{BodyStream, Client, EmptyResponse, Error, Method, Request, RequestData};
+use wiremock::matchers::{body_bytes, body_string, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const NDJSON: &str = "{\"id\":1}\n{\"id\":2}\n";
+
+struct Compressed;
+
+impl Request for Compressed {
+    type Data = ();
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/ingest".into()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+        headers
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        RequestData::Bytes(vec![0x1f, 0x8b, 0x08, 0x00])
+    }
+}
+
+struct Ndjson;
+
+impl Request for Ndjson {
+    type Data = ();
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/ingest".into()
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        RequestData::Text {
+            body: NDJSON.into(),
+            content_type: "application/x-ndjson".into(),
+        }
+    }
+}
+
+struct Upload {
+    file: PathBuf,
+}
+
+impl Request for Upload {
+    type Data = ();
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::PUT;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/ingest".into()
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        RequestData::Stream(BodyStream::file(&self.file))
+    }
+}
+
+#[derive(Clone)]
+struct PagedUpload {
+    page: Option<usize>,
+}
+
+impl From<PagedUpload> for QueryModifier {
+    fn from(s: PagedUpload) -> QueryModifier {
+        let mut data = HashMap::new();
+        if let Some(x) = s.page {
+            data.insert("page".into(), x.to_string());
+        }
+        QueryModifier { data }
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct PageResponse {
+    next_page: Option<usize>,
+}
+
+impl Request for PagedUpload {
+    type Data = ();
+    type Response = PageResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/search".into()
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        RequestData::Stream(BodyStream::new(std::io::Cursor::new(NDJSON)))
+    }
+}
+
+impl PaginatedRequest for PagedUpload {
+    type Data = Self;
+    type Paginator = QueryPaginator<PageResponse, Self>;
+    fn paginator(&self) -> Self::Paginator {
+        QueryPaginator::new(|_, r: &PageResponse| r.next_page.map(|page| Self { page: Some(page) }))
+    }
+}
+
+#[tokio::test]
+async fn bytes() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/ingest"))
+        .and(header("Content-Encoding", "gzip"))
+        .and(body_bytes(vec![0x1f, 0x8b, 0x08, 0x00]))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&Compressed).await.unwrap();
+}
+
+#[tokio::test]
+async fn text() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/ingest"))
+        .and(header("Content-Type", "application/x-ndjson"))
+        .and(body_string(NDJSON))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&Ndjson).await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_file() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    let file = std::env::temp_dir().join(format!("vila-stream-{}.ndjson", std::process::id()));
+    std::fs::write(&file, NDJSON).unwrap();
+
+    Mock::given(method("PUT"))
+        .and(path("/ingest"))
+        .and(header("Content-Length", NDJSON.len().to_string().as_str()))
+        .and(body_string(NDJSON))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let res = client.send(&Upload { file: file.clone() }).await;
+    std::fs::remove_file(&file).unwrap();
+    res.unwrap();
+}
+
+#[tokio::test]
+async fn stream_missing_file() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    let req = Upload {
+        file: "/nonexistent/vila-stream.ndjson".into(),
+    };
+    let res = client.send(&req).await;
+    assert!(matches!(res, Err(Error::Io(_))));
+}
+
+#[tokio::test]
+async fn stream_is_rebuilt_for_every_page() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/search"))
+        .and(query_param("page", "1"))
+        .and(body_string(NDJSON))
+        .respond_with(ResponseTemplate::new(200).set_body_json(PageResponse { next_page: None }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/search"))
+        .and(MissingQuery::new("page"))
+        .and(body_string(NDJSON))
+        .respond_with(ResponseTemplate::new(200).set_body_json(PageResponse { next_page: Some(1) }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pages: Vec<_> = client
+        .send_paginated(&PagedUpload { page: None })
+        .collect()
+        .await;
+    assert_eq!(pages.len(), 2);
+    for page in pages {
+        page.unwrap();
+    }
+}
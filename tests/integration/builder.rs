@@ -0,0 +1,90 @@
+use crate::utils::EmptyHello;
+use std::time::Duration;
+use vila::{Client, Error};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn sends_default_headers() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri)
+        .user_agent("my-app/1.0")
+        .default_header("X-Tenant", "acme")
+        .header_auth(vec![("X-Api-Key", "secret")])
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("User-Agent", "my-app/1.0"))
+        .and(header("X-Tenant", "acme"))
+        .and(header("X-Api-Key", "secret"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn times_out() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::builder(&uri)
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(1)))
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client.send(&EmptyHello).await.unwrap_err(),
+        Error::Reqwest(e) if e.is_timeout()
+    ));
+}
+
+#[test]
+fn rejects_invalid_base_url() {
+    assert!(matches!(
+        Client::builder("not a url").build(),
+        Err(Error::InvalidUrl { .. })
+    ));
+    assert!(matches!(
+        Client::builder("mailto:user@example.com").build(),
+        Err(Error::InvalidUrl { .. })
+    ));
+}
+
+#[test]
+fn rejects_invalid_headers() {
+    assert!(matches!(
+        Client::builder("http://localhost")
+            .default_header("Invalid Header", "value")
+            .build(),
+        Err(Error::InvalidHeader { .. })
+    ));
+    assert!(matches!(
+        Client::builder("http://localhost")
+            .header_auth(vec![("X-Api-Key", "line\nbreak")])
+            .build(),
+        Err(Error::InvalidHeader { .. })
+    ));
+}
+
+#[test]
+fn rejects_invalid_proxy() {
+    assert!(matches!(
+        Client::builder("http://localhost")
+            .proxy("not a url")
+            .build(),
+        Err(Error::InvalidUrl { .. })
+    ));
+}
@@ -1,9 +1,35 @@
 use crate::utils::{FormHello, JsonHello, NameGreeting, QueryHello};
+use serde::Serialize;
 use serde_json::json;
-use vila::Client;
+use std::borrow::Cow;
+use vila::{Client, Method, Request, RequestData};
 use wiremock::matchers::{body_json, body_string, header, method, path, query_param};
 use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
 
+#[derive(Serialize)]
+struct Search {
+    term: String,
+    dry_run: bool,
+}
+
+impl Request for Search {
+    type Data = Self;
+    type Response = NameGreeting;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/search".into()
+    }
+
+    fn query(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        vec![("dry_run".into(), self.dry_run.to_string().into())]
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Json(self)
+    }
+}
+
 #[tokio::test]
 async fn query() {
     let _ = env_logger::try_init();
@@ -109,3 +135,32 @@ async fn form() {
         }
     );
 }
+
+#[tokio::test]
+async fn query_with_json_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).query_auth(vec![("key", "k")]);
+
+    Mock::given(method("POST"))
+        .and(path("/search"))
+        .and(query_param("dry_run", "true"))
+        .and(query_param("key", "k"))
+        .and(body_json(json!({"term": "vila", "dry_run": true})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(NameGreeting {
+            message: "Found!".into(),
+        }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .send(&Search {
+            term: "vila".into(),
+            dry_run: true,
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.message, "Found!");
+}
@@ -0,0 +1,277 @@
+use crate::utils::matchers::{MissingHeader, MissingQuery};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use vila::pagination::PaginatedRequest;
+use vila::{Client, EmptyResponse, Method, Request};
+use wiremock::matchers::{body_json, body_string, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[derive(Serialize)]
+struct NewPost {
+    title: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct Post {
+    id: u64,
+}
+
+#[derive(Request)]
+#[vila(method = "post", path = "/users/{id}/posts", response = Post)]
+struct CreatePost {
+    #[vila(path)]
+    id: String,
+    #[vila(query)]
+    dry_run: bool,
+    #[vila(query = "tag")]
+    label: Option<String>,
+    #[vila(header = "X-Request-Id")]
+    request_id: Option<u32>,
+    #[vila(body)]
+    post: NewPost,
+    #[allow(dead_code)]
+    note: &'static str,
+}
+
+#[derive(Serialize)]
+struct Login {
+    user: String,
+}
+
+#[derive(Request)]
+#[vila(method = "PUT", path = "/orgs/{org}/sessions/{session}", body = form)]
+struct UpdateSession {
+    #[vila(path = "org")]
+    r#type: u32,
+    #[vila(path)]
+    session: &'static str,
+    #[vila(header)]
+    x_trace: &'static str,
+    #[vila(body)]
+    login: Login,
+}
+
+#[derive(Request)]
+#[vila(path = "/health")]
+struct Health;
+
+#[derive(Deserialize, Serialize)]
+struct Meta {
+    total_pages: u32,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Users {
+    meta: Meta,
+    users: Vec<String>,
+}
+
+#[derive(Request, PaginatedRequest)]
+#[vila(path = "/users", response = Users)]
+#[paginate(query = "page", start = 0, until = "meta.total_pages")]
+struct ListUsers {
+    #[vila(query)]
+    per_page: u32,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Events {
+    next: Option<String>,
+    events: Vec<String>,
+}
+
+#[derive(Request, PaginatedRequest)]
+#[vila(path = "/events", response = Events)]
+#[paginate(cursor = "next", param = "after")]
+struct ListEvents;
+
+#[tokio::test]
+async fn derived_request() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    assert_eq!(CreatePost::METHOD, Method::POST);
+    Mock::given(method("POST"))
+        .and(path("/users/jane%20doe/posts"))
+        .and(query_param("dry_run", "true"))
+        .and(MissingQuery::new("tag"))
+        .and(header("X-Request-Id", "7"))
+        .and(body_json(json!({"title": "Hello"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Post { id: 1 }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let req = CreatePost {
+        id: "jane doe".into(),
+        dry_run: true,
+        label: None,
+        request_id: Some(7),
+        post: NewPost {
+            title: "Hello".into(),
+        },
+        note: "not sent",
+    };
+    assert_eq!(client.send(&req).await.unwrap(), Post { id: 1 });
+}
+
+#[tokio::test]
+async fn derived_request_with_optional_fields() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("POST"))
+        .and(path("/users/1/posts"))
+        .and(query_param("tag", "news"))
+        .and(MissingHeader::new("X-Request-Id"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Post { id: 2 }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let req = CreatePost {
+        id: "1".into(),
+        dry_run: false,
+        label: Some("news".into()),
+        request_id: None,
+        post: NewPost {
+            title: "Hello".into(),
+        },
+        note: "not sent",
+    };
+    assert_eq!(client.send(&req).await.unwrap(), Post { id: 2 });
+}
+
+#[tokio::test]
+async fn derived_request_with_form_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("PUT"))
+        .and(path("/orgs/3/sessions/a%2Fb"))
+        .and(header("x-trace", "abc"))
+        .and(header("content-type", "application/x-www-form-urlencoded"))
+        .and(body_string("user=jane"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let req = UpdateSession {
+        r#type: 3,
+        session: "a/b",
+        x_trace: "abc",
+        login: Login {
+            user: "jane".into(),
+        },
+    };
+    let _: EmptyResponse = client.send(&req).await.unwrap();
+}
+
+#[tokio::test]
+async fn derived_request_skips_invalid_header() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("PUT"))
+        .and(path("/orgs/3/sessions/a"))
+        .and(MissingHeader::new("x-trace"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let req = UpdateSession {
+        r#type: 3,
+        session: "a",
+        x_trace: "abc\ndef",
+        login: Login {
+            user: "jane".into(),
+        },
+    };
+    let _: EmptyResponse = client.send(&req).await.unwrap();
+}
+
+#[tokio::test]
+async fn derived_unit_request() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/health"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&Health).await.unwrap();
+}
+
+#[tokio::test]
+async fn derived_page_number_pagination() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    for page in 0..3 {
+        Mock::given(method("GET"))
+            .and(path("/users"))
+            .and(query_param("per_page", "2"))
+            .and(query_param("page", page.to_string().as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(Users {
+                meta: Meta { total_pages: 3 },
+                users: vec![format!("user-{}", page)],
+            }))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let req = ListUsers { per_page: 2 };
+    let pages: Vec<Users> = client.send_paginated(&req).try_collect().await.unwrap();
+    let users: Vec<_> = pages.into_iter().flat_map(|page| page.users).collect();
+    assert_eq!(users, vec!["user-0", "user-1", "user-2"]);
+}
+
+#[tokio::test]
+async fn derived_cursor_pagination() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    Mock::given(method("GET"))
+        .and(path("/events"))
+        .and(MissingQuery::new("after"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Events {
+            next: Some("abc".into()),
+            events: vec!["first".into()],
+        }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/events"))
+        .and(query_param("after", "abc"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(Events {
+            next: None,
+            events: vec!["last".into()],
+        }))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pages: Vec<Events> = client
+        .send_paginated(&ListEvents)
+        .try_collect()
+        .await
+        .unwrap();
+    let events: Vec<_> = pages.into_iter().flat_map(|page| page.events).collect();
+    assert_eq!(events, vec!["first", "last"]);
+}
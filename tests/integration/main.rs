@@ -1,7 +1,21 @@
 mod authorization;
+#[cfg(feature = "blocking")]
+mod blocking;
+mod body;
+mod builder;
 mod data;
+#[cfg(feature = "derive")]
+mod derive;
 mod empty_response;
 mod errors;
+mod middleware;
+mod multipart;
 mod pagination;
 mod post;
+mod rate_limit;
+mod retry;
+mod timeout;
+mod tls;
+mod transport;
+mod url;
 mod utils;
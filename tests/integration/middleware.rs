@@ -0,0 +1,135 @@
+use crate::utils::{EmptyHello, NameGreeting, QueryHello};
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use std::sync::{Arc, Mutex};
+use vila::header::{HeaderName, HeaderValue};
+use vila::{Client, Middleware, Next, Result};
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct AppendHeader(&'static str, &'static str);
+
+impl Middleware for AppendHeader {
+    fn handle<'a>(
+        &'a self,
+        mut request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response>> {
+        request.headers_mut().append(
+            HeaderName::from_static(self.0),
+            HeaderValue::from_static(self.1),
+        );
+        next.run(request)
+    }
+}
+
+struct Canned;
+
+impl Middleware for Canned {
+    fn handle<'a>(
+        &'a self,
+        _request: reqwest::Request,
+        _next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response>> {
+        async move {
+            let response = http::Response::builder()
+                .status(200)
+                .body(r#"{"message": "Hello from cache!"}"#)
+                .unwrap();
+            Ok(response.into())
+        }
+        .boxed()
+    }
+}
+
+struct Record(&'static str, Arc<Mutex<Vec<String>>>);
+
+impl Middleware for Record {
+    fn handle<'a>(
+        &'a self,
+        request: reqwest::Request,
+        next: Next<'a>,
+    ) -> BoxFuture<'a, Result<reqwest::Response>> {
+        async move {
+            self.1.lock().unwrap().push(format!("{} request", self.0));
+            let response = next.run(request).await?;
+            self.1.lock().unwrap().push(format!("{} response", self.0));
+            Ok(response)
+        }
+        .boxed()
+    }
+}
+
+#[tokio::test]
+async fn modifies_request() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_middleware(AppendHeader("x-request-id", "abc"));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .and(header("x-request-id", "abc"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn runs_in_order() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let client = Client::new(&uri)
+        .with_middleware(Record("first", log.clone()))
+        .with_middleware(Record("second", log.clone()));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "first request",
+            "second request",
+            "second response",
+            "first response"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn short_circuits_request() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_middleware(Canned);
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let response = client
+        .send(&QueryHello {
+            name: "world".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        NameGreeting {
+            message: "Hello from cache!".into()
+        }
+    );
+}
@@ -0,0 +1,172 @@
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::time::Duration;
+use vila::multipart::Part;
+use vila::{
+    Client, EmptyResponse, Error, Method, Multipart, Request, RequestData, RetryPolicy, StatusCode,
+};
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct Upload {
+    file: PathBuf,
+}
+
+impl Request for Upload {
+    type Data = ();
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/upload".into()
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        let form = Multipart::new()
+            .text("title", "Report")
+            .bytes("checksum", b"abc".to_vec())
+            .part(
+                "attachment",
+                Part::file(&self.file).content_type("text/csv"),
+            )
+            .part(
+                "thumbnail",
+                Part::bytes(vec![1, 2, 3])
+                    .file_name("thumb.png")
+                    .content_type("image/png"),
+            );
+        RequestData::Multipart(form)
+    }
+}
+
+struct Comment;
+
+impl Request for Comment {
+    type Data = ();
+    type Response = EmptyResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/upload".into()
+    }
+
+    fn data(&self) -> RequestData<&()> {
+        let form = Multipart::new().text("title", "Report").part(
+            "thumbnail",
+            Part::bytes(vec![1, 2, 3])
+                .file_name("thumb.png")
+                .content_type("image/png"),
+        );
+        RequestData::Multipart(form)
+    }
+}
+
+fn retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 1,
+        jitter: false,
+        initial_backoff: Duration::from_millis(10),
+        ..Default::default()
+    }
+}
+
+fn is_multipart(req: &wiremock::Request) -> bool {
+    req.headers
+        .get(&"Content-Type".into())
+        .is_some_and(|v| v.as_str().starts_with("multipart/form-data; boundary="))
+}
+
+#[tokio::test]
+async fn multipart_upload() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+    let file = std::env::temp_dir().join(format!("vila-upload-{}.csv", std::process::id()));
+    std::fs::write(&file, "id,name\n1,vila\n").unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(is_multipart)
+        .and(body_string_contains("name=\"title\"\r\n\r\nReport"))
+        .and(body_string_contains("name=\"checksum\"\r\n\r\nabc"))
+        .and(body_string_contains(format!(
+            "name=\"attachment\"; filename=\"{}\"\r\nContent-Type: text/csv\r\n\r\nid,name\n1,vila\n",
+            file.file_name().unwrap().to_str().unwrap()
+        )))
+        .and(body_string_contains(
+            "name=\"thumbnail\"; filename=\"thumb.png\"\r\nContent-Type: image/png",
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let res = client.send(&Upload { file: file.clone() }).await;
+    std::fs::remove_file(&file).unwrap();
+    res.unwrap();
+}
+
+#[tokio::test]
+async fn multipart_missing_file() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri());
+
+    let req = Upload {
+        file: "/nonexistent/vila-upload.csv".into(),
+    };
+    let res = client.send(&req).await;
+    assert!(matches!(res, Err(Error::Io(_))));
+}
+
+#[tokio::test]
+async fn in_memory_multipart_is_retried() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri()).with_retry_policy(retry_policy());
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .and(is_multipart)
+        .and(body_string_contains("name=\"title\"\r\n\r\nReport\r\n"))
+        .and(body_string_contains(
+            "name=\"thumbnail\"; filename=\"thumb.png\"\r\nContent-Type: image/png\r\n\r\n\u{1}\u{2}\u{3}\r\n",
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&Comment).await.unwrap();
+}
+
+#[tokio::test]
+async fn file_multipart_is_not_retried() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri()).with_retry_policy(retry_policy());
+    let file = std::env::temp_dir().join(format!("vila-retry-{}.csv", std::process::id()));
+    std::fs::write(&file, "id,name\n1,vila\n").unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/upload"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let res = client.send(&Upload { file: file.clone() }).await;
+    std::fs::remove_file(&file).unwrap();
+    assert!(matches!(
+        res.unwrap_err(),
+        Error::ServerError(status, _) if status == StatusCode::SERVICE_UNAVAILABLE
+    ));
+}
@@ -4,7 +4,7 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use vila::pagination::path::*;
 use vila::pagination::*;
-use vila::{Client, Request};
+use vila::{Client, PathParams, Request};
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
 
@@ -36,7 +36,7 @@ impl Request for PaginationRequest {
     type Data = ();
     type Response = PaginationResponse;
 
-    fn endpoint(&self) -> Cow<str> {
+    fn endpoint(&self) -> Cow<'_, str> {
         match self.page {
             Some(page) => format!("/nested/page/{}", page).into(),
             None => "/nested/page".into(),
@@ -54,6 +54,49 @@ impl PaginatedRequest for PaginationRequest {
     }
 }
 
+#[derive(Clone)]
+struct UserPostsRequest {
+    user: String,
+    page: usize,
+}
+
+impl From<UserPostsRequest> for PathParamModifier {
+    fn from(s: UserPostsRequest) -> PathParamModifier {
+        let mut data = HashMap::new();
+        data.insert("page".to_string(), s.page.to_string());
+        PathParamModifier { data }
+    }
+}
+
+impl Request for UserPostsRequest {
+    type Data = ();
+    type Response = PaginationResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/users/{user}/posts/page/{page}".into()
+    }
+
+    fn path_params(&self) -> PathParams {
+        PathParams::new()
+            .param("user", &self.user)
+            .param("page", self.page)
+    }
+}
+
+impl PaginatedRequest for UserPostsRequest {
+    type Data = Self;
+    type Paginator = PathPaginator<PaginationResponse, Self, PathParamModifier>;
+    fn paginator(&self) -> Self::Paginator {
+        let user = self.user.clone();
+        PathPaginator::with_modifier(move |_, r: &PaginationResponse| {
+            r.next_page.map(|page| Self {
+                user: user.clone(),
+                page,
+            })
+        })
+    }
+}
+
 #[tokio::test]
 async fn path_pagination() {
     let _ = env_logger::try_init();
@@ -112,3 +155,61 @@ async fn path_pagination() {
     );
     assert!(response.next().await.is_none());
 }
+
+#[tokio::test]
+async fn path_pagination_by_name() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/users/jane%20doe/posts/page/0"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(PaginationResponse {
+                next_page: Some(1),
+                data: "First!".into(),
+            }),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/users/jane%20doe/posts/page/1"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(PaginationResponse {
+                next_page: None,
+                data: "Last!".into(),
+            }),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = UserPostsRequest {
+        user: "jane doe".into(),
+        page: 0,
+    };
+    let mut response = client.send_paginated(&request);
+    assert_eq!(response.next().await.unwrap().unwrap().data, "First!");
+    assert_eq!(response.next().await.unwrap().unwrap().data, "Last!");
+    assert!(response.next().await.is_none());
+}
+
+#[test]
+fn path_paginator_infers_default_modifier() {
+    let paginator = PathPaginator::new(|_: Option<&PaginationRequest>, r: &PaginationResponse| {
+        r.next_page
+            .map(|page| PaginationRequest { page: Some(page) })
+    });
+    let response = PaginationResponse {
+        data: String::new(),
+        next_page: Some(2),
+    };
+    let modifier = match paginator.next(None, &response) {
+        State::Next(data) => paginator.modifier(data),
+        _ => panic!("expected another page"),
+    };
+    assert_eq!(modifier.data.get(&2).map(String::as_str), Some("2"));
+}
@@ -5,8 +5,8 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use vila::pagination::query::*;
 use vila::pagination::*;
-use vila::{Client, Request, RequestData};
-use wiremock::matchers::{method, path, query_param};
+use vila::{Client, Method, Request, RequestData};
+use wiremock::matchers::{body_json, method, path, query_param};
 use wiremock::{Mock, MockServer, Request as MockRequest, ResponseTemplate};
 
 #[derive(Clone, Serialize)]
@@ -34,7 +34,7 @@ impl Request for PaginationRequest {
     type Data = Self;
     type Response = PaginationResponse;
 
-    fn endpoint(&self) -> Cow<str> {
+    fn endpoint(&self) -> Cow<'_, str> {
         "/page".into()
     }
 
@@ -53,6 +53,50 @@ impl PaginatedRequest for PaginationRequest {
     }
 }
 
+#[derive(Clone, Serialize)]
+struct SearchRequest {
+    term: String,
+    page: Option<usize>,
+}
+
+impl From<SearchRequest> for QueryModifier {
+    fn from(s: SearchRequest) -> QueryModifier {
+        PaginationRequest { page: s.page }.into()
+    }
+}
+
+impl Request for SearchRequest {
+    type Data = Self;
+    type Response = PaginationResponse;
+    const METHOD: Method = Method::POST;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/search".into()
+    }
+
+    fn query(&self) -> Vec<(Cow<'_, str>, Cow<'_, str>)> {
+        vec![("page".into(), "0".into()), ("sort".into(), "asc".into())]
+    }
+
+    fn data(&self) -> RequestData<&Self> {
+        RequestData::Json(self)
+    }
+}
+
+impl PaginatedRequest for SearchRequest {
+    type Data = Self;
+    type Paginator = QueryPaginator<PaginationResponse, Self>;
+    fn paginator(&self) -> Self::Paginator {
+        let term = self.term.clone();
+        QueryPaginator::new(move |_, r: &PaginationResponse| {
+            r.next_page.map(|page| Self {
+                term: term.clone(),
+                page: Some(page),
+            })
+        })
+    }
+}
+
 #[tokio::test]
 async fn query_pagination() {
     let _ = env_logger::try_init();
@@ -158,3 +202,50 @@ async fn can_overwrite_existing_query() {
         "Second!".to_string()
     );
 }
+
+#[tokio::test]
+async fn pagination_with_request_query_and_body() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("POST"))
+        .and(path("/search"))
+        .and(query_param("page", "0"))
+        .and(query_param("sort", "asc"))
+        .and(body_json(serde_json::json!({"term": "vila", "page": null})))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(PaginationResponse {
+                next_page: Some(1),
+                data: "First!".into(),
+            }),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/search"))
+        .and(query_param("page", "1"))
+        .and(query_param("sort", "asc"))
+        .and(|req: &MockRequest| req.url.query_pairs().filter(|(k, _)| k == "page").count() == 1)
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(PaginationResponse {
+                next_page: None,
+                data: "Last!".into(),
+            }),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let request = SearchRequest {
+        term: "vila".into(),
+        page: None,
+    };
+    let mut response = client.send_paginated(&request);
+    assert_eq!(response.next().await.unwrap().unwrap().data, "First!");
+    assert_eq!(response.next().await.unwrap().unwrap().data, "Last!");
+    assert!(response.next().await.is_none());
+}
@@ -0,0 +1,254 @@
+use crate::utils::EmptyHello;
+use std::borrow::Cow;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use vila::{Client, EmptyResponse, RateLimit, RateLimitStatus, Request};
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct EmptyReport;
+
+impl Request for EmptyReport {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/reports/daily".into()
+    }
+}
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn limits_request_rate() {
+    let _ = env_logger::try_init();
+    let server = server().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).rate_limit(RateLimit::per_second(4).burst(1));
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        client.send(&EmptyHello).await.unwrap();
+    }
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn limit_is_shared_between_clones() {
+    let _ = env_logger::try_init();
+    let server = server().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).rate_limit(RateLimit::per_second(4).burst(2));
+    let clone = client.clone();
+
+    let start = Instant::now();
+    client.send(&EmptyHello).await.unwrap();
+    client.send(&EmptyHello).await.unwrap();
+    clone.send(&EmptyHello).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(250));
+}
+
+#[tokio::test]
+async fn endpoint_limits_use_separate_budgets() {
+    let _ = env_logger::try_init();
+    let server = server().await;
+    let uri = server.uri();
+    let client = Client::new(&uri)
+        .rate_limit(RateLimit::per_second(100))
+        .rate_limit_endpoint("/reports", RateLimit::per_second(2).burst(1));
+
+    let start = Instant::now();
+    for _ in 0..10 {
+        client.send(&EmptyHello).await.unwrap();
+    }
+    assert!(start.elapsed() < Duration::from_millis(500));
+
+    let start = Instant::now();
+    client.send(&EmptyReport).await.unwrap();
+    client.send(&EmptyReport).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
+
+#[test]
+#[should_panic(expected = "at least one request")]
+fn rejects_zero_requests() {
+    RateLimit::per_second(0);
+}
+
+#[test]
+#[should_panic(expected = "period must be non-zero")]
+fn rejects_zero_period() {
+    RateLimit::new(10, Duration::ZERO);
+}
+
+#[tokio::test]
+async fn reads_rate_limit_headers() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+    let reset = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        + 60;
+
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("X-RateLimit-Limit", "100")
+                .insert_header("X-RateLimit-Remaining", "42")
+                .insert_header("X-RateLimit-Reset", reset.to_string().as_str()),
+        )
+        .mount(&server)
+        .await;
+
+    assert_eq!(client.rate_limit_status(), None);
+    client.send(&EmptyHello).await.unwrap();
+    assert_eq!(
+        client.rate_limit_status(),
+        Some(RateLimitStatus {
+            limit: Some(100),
+            remaining: Some(42),
+            reset: Some(UNIX_EPOCH + Duration::from_secs(reset)),
+        })
+    );
+}
+
+#[tokio::test]
+async fn reads_ietf_rate_limit_header() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("RateLimit", "limit=10, remaining=5, reset=30"),
+        )
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    let status = client.rate_limit_status().unwrap();
+    assert_eq!(status.limit, Some(10));
+    assert_eq!(status.remaining, Some(5));
+    assert!(status.reset.unwrap() > SystemTime::now());
+}
+
+#[tokio::test]
+async fn waits_for_reset_when_quota_is_exhausted() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).adaptive_throttling();
+
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("RateLimit-Limit", "10")
+                .insert_header("RateLimit-Remaining", "0")
+                .insert_header("RateLimit-Reset", "1"),
+        )
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    let start = Instant::now();
+    client.send(&EmptyHello).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(900));
+}
+
+#[tokio::test]
+async fn spreads_out_concurrent_requests() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).adaptive_throttling();
+
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("RateLimit-Limit", "100")
+                .insert_header("RateLimit-Remaining", "10")
+                .insert_header("RateLimit-Reset", "2"),
+        )
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    // Each request waits about 200ms, but only after the requests before it
+    let start = Instant::now();
+    let (a, b, c) = futures::join!(
+        client.send(&EmptyHello),
+        client.send(&EmptyHello),
+        client.send(&EmptyHello)
+    );
+    a.unwrap();
+    b.unwrap();
+    c.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn ignores_implausible_reset_times() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).adaptive_throttling();
+    let reset = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+        + 60_000;
+
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("RateLimit-Limit", "10")
+                .insert_header("RateLimit-Remaining", "0")
+                .insert_header("RateLimit-Reset", reset.to_string().as_str()),
+        )
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    assert_eq!(client.rate_limit_status().unwrap().reset, None);
+    let start = Instant::now();
+    client.send(&EmptyHello).await.unwrap();
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn caps_throttle_delay() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri)
+        .adaptive_throttling()
+        .max_throttle_delay(Duration::from_millis(200));
+
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("RateLimit-Limit", "10")
+                .insert_header("RateLimit-Remaining", "0")
+                .insert_header("RateLimit-Reset", "3600"),
+        )
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+    let start = Instant::now();
+    client.send(&EmptyHello).await.unwrap();
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(150));
+    assert!(elapsed < Duration::from_secs(2));
+}
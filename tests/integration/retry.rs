@@ -0,0 +1,328 @@
+use crate::utils::matchers::MissingQuery;
+use crate::utils::{EmptyHello, PagedHello};
+use futures::StreamExt;
+use serde_json::json;
+use std::borrow::Cow;
+use std::time::{Duration, Instant, SystemTime};
+use vila::{Client, EmptyResponse, Error, Request, RetryPolicy, StatusCode};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 2,
+        jitter: false,
+        initial_backoff: Duration::from_millis(10),
+        ..Default::default()
+    }
+}
+
+struct NoRetryHello;
+
+impl Request for NoRetryHello {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/hello".into()
+    }
+
+    fn retry_policy(&self) -> Option<RetryPolicy> {
+        Some(RetryPolicy {
+            max_retries: 0,
+            ..Default::default()
+        })
+    }
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn gives_up_after_max_retries() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(3)
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client.send(&EmptyHello).await.unwrap_err(),
+        Error::ServerError(status, _) if status == StatusCode::INTERNAL_SERVER_ERROR
+    ));
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors_by_default() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client.send(&EmptyHello).await.unwrap_err(),
+        Error::ClientError(status, _) if status == StatusCode::BAD_REQUEST
+    ));
+}
+
+struct QuickHello;
+
+impl Request for QuickHello {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/hello".into()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_millis(100))
+    }
+}
+
+#[tokio::test]
+async fn retries_timeouts() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(1)))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&QuickHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn request_policy_overrides_client_policy() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(client.send(&NoRetryHello).await.is_err());
+}
+
+#[tokio::test]
+async fn honors_retry_after_seconds() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    client.send(&EmptyHello).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn honors_retry_after_date() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+    let retry_at = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(2));
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", retry_at.as_str()))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    client.send(&EmptyHello).await.unwrap();
+    // HTTP-dates have a resolution of one second
+    assert!(start.elapsed() >= Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn caps_retry_after_at_max_retry_after() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(RetryPolicy {
+        max_retry_after: Duration::from_millis(100),
+        ..fast_policy()
+    });
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3600"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let start = Instant::now();
+    client.send(&EmptyHello).await.unwrap();
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(100));
+    assert!(elapsed < Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn retries_throttled_responses_without_policy() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn does_not_retry_server_errors_without_policy() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(503))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client.send(&EmptyHello).await.unwrap_err(),
+        Error::ServerError(status, _) if status == StatusCode::SERVICE_UNAVAILABLE
+    ));
+}
+
+#[tokio::test]
+async fn retries_throttled_pages() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri).with_retry_policy(fast_policy());
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(MissingQuery::new("page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "next_page": 1 })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .and(query_param("page", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "next_page": null })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let pages: Vec<_> = client.send_paginated(&PagedHello).collect().await;
+    assert_eq!(pages.len(), 2);
+    assert!(pages.iter().all(|page| page.is_ok()));
+}
@@ -0,0 +1,113 @@
+use crate::utils::{EmptyHello, Page, PageResponse};
+use futures::StreamExt;
+use serde_json::json;
+use std::borrow::Cow;
+use std::time::Duration;
+use vila::pagination::query::QueryPaginator;
+use vila::pagination::PaginatedRequest;
+use vila::{Client, EmptyResponse, Error, Request};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct HealthCheck;
+
+impl Request for HealthCheck {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/hello".into()
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(Duration::from_millis(100))
+    }
+}
+
+struct SlowExport;
+
+impl Request for SlowExport {
+    type Data = ();
+    type Response = PageResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/page".into()
+    }
+}
+
+impl PaginatedRequest for SlowExport {
+    type Data = Page;
+    type Paginator = QueryPaginator<PageResponse, Page>;
+
+    fn paginator(&self) -> Self::Paginator {
+        QueryPaginator::new(|prev: Option<&Page>, _: &PageResponse| {
+            Some(Page(prev.map_or(1, |p| p.0 + 1)))
+        })
+    }
+
+    fn deadline(&self) -> Option<Duration> {
+        Some(Duration::from_millis(500))
+    }
+}
+
+#[tokio::test]
+async fn request_timeout() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/hello"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+        .mount(&server)
+        .await;
+
+    assert!(matches!(
+        client.send(&HealthCheck).await.unwrap_err(),
+        Error::Reqwest(e) if e.is_timeout()
+    ));
+    // Requests without a timeout are unaffected
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn pagination_deadline() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "next_page": null }))
+                .set_delay(Duration::from_millis(200)),
+        )
+        .mount(&server)
+        .await;
+
+    let pages: Vec<_> = client.send_paginated(&SlowExport).collect().await;
+    assert_eq!(pages.len(), 3);
+    assert!(pages[..2].iter().all(|page| page.is_ok()));
+    assert!(matches!(pages[2], Err(Error::DeadlineExceeded)));
+}
+
+#[tokio::test]
+async fn pagination_deadline_starts_on_first_poll() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let uri = server.uri();
+    let client = Client::new(&uri);
+
+    Mock::given(method("GET"))
+        .and(path("/page"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "next_page": null })))
+        .mount(&server)
+        .await;
+
+    let mut pages = client.send_paginated(&SlowExport);
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    pages.next().await.unwrap().unwrap();
+}
@@ -0,0 +1,192 @@
+use crate::utils::EmptyHello;
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa, SanType};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::rustls::{self, PrivateKey, RootCertStore, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use vila::Client;
+
+struct Certs {
+    ca_pem: String,
+    ca_der: Vec<u8>,
+    server_der: Vec<u8>,
+    server_key: Vec<u8>,
+    // Client identities can only be used with one of the TLS features
+    #[cfg_attr(
+        not(any(feature = "native-tls", feature = "rustls-tls")),
+        allow(dead_code)
+    )]
+    client_pem: String,
+    #[cfg_attr(
+        not(any(feature = "native-tls", feature = "rustls-tls")),
+        allow(dead_code)
+    )]
+    client_key_pem: String,
+}
+
+impl Certs {
+    fn generate() -> Self {
+        let mut params = CertificateParams::new(Vec::new());
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "vila test CA");
+        let ca = Certificate::from_params(params).unwrap();
+
+        let mut params = CertificateParams::new(Vec::new());
+        params.subject_alt_names = vec![SanType::IpAddress(IpAddr::V4(Ipv4Addr::LOCALHOST))];
+        let server = Certificate::from_params(params).unwrap();
+
+        let mut params = CertificateParams::new(Vec::new());
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "vila test client");
+        let client = Certificate::from_params(params).unwrap();
+
+        Self {
+            ca_pem: ca.serialize_pem().unwrap(),
+            ca_der: ca.serialize_der().unwrap(),
+            server_der: server.serialize_der_with_signer(&ca).unwrap(),
+            server_key: server.serialize_private_key_der(),
+            client_pem: client.serialize_pem_with_signer(&ca).unwrap(),
+            client_key_pem: client.serialize_private_key_pem(),
+        }
+    }
+
+    fn server_config(&self, client_auth: bool) -> ServerConfig {
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = if client_auth {
+            let mut roots = RootCertStore::empty();
+            roots
+                .add(&rustls::Certificate(self.ca_der.clone()))
+                .unwrap();
+            builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots).boxed())
+        } else {
+            builder.with_no_client_auth()
+        };
+        builder
+            .with_single_cert(
+                vec![rustls::Certificate(self.server_der.clone())],
+                PrivateKey(self.server_key.clone()),
+            )
+            .unwrap()
+    }
+}
+
+/// Starts an HTTPS server that responds to every request with an empty 200 response.
+async fn serve(config: ServerConfig) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                // Handshakes fail when the client doesn't present a valid certificate
+                let mut stream = match acceptor.accept(stream).await {
+                    Ok(stream) => stream,
+                    Err(_) => return,
+                };
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    match stream.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let response = b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n";
+                let _ = stream.write_all(response).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    });
+    format!("https://{}", addr)
+}
+
+#[tokio::test]
+async fn custom_root_certificate() {
+    let _ = env_logger::try_init();
+    let certs = Certs::generate();
+    let uri = serve(certs.server_config(false)).await;
+
+    let client = Client::builder(&uri)
+        .add_root_certificate(&certs.ca_pem)
+        .tls_built_in_root_certs(false)
+        .build()
+        .unwrap();
+    client.send(&EmptyHello).await.unwrap();
+
+    // Without the CA, the server certificate isn't trusted
+    let client = Client::builder(&uri).build().unwrap();
+    assert!(client.send(&EmptyHello).await.is_err());
+}
+
+#[cfg(feature = "native-tls")]
+#[tokio::test]
+async fn mutual_tls_pkcs8() {
+    let _ = env_logger::try_init();
+    let certs = Certs::generate();
+    let uri = serve(certs.server_config(true)).await;
+
+    let client = Client::builder(&uri)
+        .add_root_certificate(&certs.ca_pem)
+        .identity_pkcs8_pem(&certs.client_pem, &certs.client_key_pem)
+        .build()
+        .unwrap();
+    client.send(&EmptyHello).await.unwrap();
+
+    let client = Client::builder(&uri)
+        .add_root_certificate(&certs.ca_pem)
+        .build()
+        .unwrap();
+    assert!(client.send(&EmptyHello).await.is_err());
+}
+
+#[cfg(feature = "rustls-tls")]
+#[tokio::test]
+async fn mutual_tls_pem() {
+    let _ = env_logger::try_init();
+    let certs = Certs::generate();
+    let uri = serve(certs.server_config(true)).await;
+    let identity = format!("{}{}", certs.client_pem, certs.client_key_pem);
+
+    let client = Client::builder(&uri)
+        .add_root_certificate(&certs.ca_pem)
+        .identity_pem(&identity)
+        .build()
+        .unwrap();
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[cfg(feature = "rustls-tls")]
+#[tokio::test]
+async fn pinned_certificate() {
+    use sha2::{Digest, Sha256};
+
+    let _ = env_logger::try_init();
+    let certs = Certs::generate();
+    let uri = serve(certs.server_config(true)).await;
+    let identity = format!("{}{}", certs.client_pem, certs.client_key_pem);
+    let fingerprint = hex::encode(Sha256::digest(&certs.server_der));
+
+    let client = Client::builder(&uri)
+        .add_root_certificate(&certs.ca_pem)
+        .identity_pem(&identity)
+        .pin_certificate_sha256(&fingerprint)
+        .build()
+        .unwrap();
+    client.send(&EmptyHello).await.unwrap();
+
+    let client = Client::builder(&uri)
+        .add_root_certificate(&certs.ca_pem)
+        .identity_pem(&identity)
+        .pin_certificate_sha256("00".repeat(32))
+        .build()
+        .unwrap();
+    assert!(client.send(&EmptyHello).await.is_err());
+}
@@ -0,0 +1,71 @@
+use crate::utils::{NameGreeting, PagedHello, QueryHello};
+use futures::future::BoxFuture;
+use futures::{FutureExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use vila::{Client, Result, Transport};
+
+/// Serves responses from memory, keyed by the request's path and query.
+#[derive(Default)]
+struct InMemory {
+    responses: HashMap<String, &'static str>,
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl InMemory {
+    fn respond(mut self, path_and_query: &str, body: &'static str) -> Self {
+        self.responses.insert(path_and_query.into(), body);
+        self
+    }
+}
+
+impl Transport for InMemory {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<reqwest::Response>> {
+        let url = request.url();
+        let key = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.requests.lock().unwrap().push(key.clone());
+        let response = match self.responses.get(&key) {
+            Some(body) => http::Response::builder().status(200).body(*body),
+            None => http::Response::builder().status(404).body(""),
+        };
+        futures::future::ready(Ok(response.unwrap().into())).boxed()
+    }
+}
+
+#[tokio::test]
+async fn sends_through_custom_transport() {
+    let _ = env_logger::try_init();
+    let transport =
+        InMemory::default().respond("/hello?name=world", r#"{"message": "Hello, world!"}"#);
+    let client = Client::new("http://localhost").with_transport(transport);
+
+    let response = client
+        .send(&QueryHello {
+            name: "world".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        response,
+        NameGreeting {
+            message: "Hello, world!".into()
+        }
+    );
+}
+
+#[tokio::test]
+async fn paginates_through_custom_transport() {
+    let _ = env_logger::try_init();
+    let transport = InMemory::default()
+        .respond("/page", r#"{"next_page": 1}"#)
+        .respond("/page?page=1", r#"{"next_page": null}"#);
+    let requests = transport.requests.clone();
+    let client = Client::new("http://localhost").with_transport(transport);
+
+    let pages: Vec<_> = client.send_paginated(&PagedHello).collect().await;
+    assert!(pages.iter().all(|page| page.is_ok()));
+    assert_eq!(*requests.lock().unwrap(), vec!["/page", "/page?page=1"]);
+}
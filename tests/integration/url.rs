@@ -0,0 +1,235 @@
+use crate::utils::matchers::MissingHeader;
+use crate::utils::{EmptyHello, QueryHello};
+use std::borrow::Cow;
+use vila::{Client, EmptyResponse, Error, PathParams, Request, RequestData};
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+struct Endpoint(String);
+
+impl Request for Endpoint {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        self.0.as_str().into()
+    }
+}
+
+struct SearchHello(QueryHello);
+
+impl Request for SearchHello {
+    type Data = QueryHello;
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/hello?lang=sv".into()
+    }
+
+    fn data(&self) -> RequestData<&QueryHello> {
+        RequestData::Query(&self.0)
+    }
+}
+
+struct UserPost {
+    user: &'static str,
+    post: Option<u32>,
+}
+
+impl Request for UserPost {
+    type Data = ();
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/users/{id}/posts/{post_id}".into()
+    }
+
+    fn path_params(&self) -> PathParams {
+        let params = PathParams::new().param("id", self.user);
+        match self.post {
+            Some(post) => params.param("post_id", post),
+            None => params,
+        }
+    }
+}
+
+async fn server(expected_path: &str) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(expected_path))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn base_url_with_trailing_slash() {
+    let _ = env_logger::try_init();
+    let server = server("/hello").await;
+    let client = Client::new(format!("{}/", server.uri()));
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn base_url_with_path_prefix() {
+    let _ = env_logger::try_init();
+    let server = server("/api/v2/hello").await;
+    let client = Client::new(format!("{}/api/v2", server.uri()));
+
+    client.send(&EmptyHello).await.unwrap();
+}
+
+#[tokio::test]
+async fn endpoint_with_query() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(format!("{}/api", server.uri()));
+
+    Mock::given(method("GET"))
+        .and(path("/api/hello"))
+        .and(query_param("lang", "sv"))
+        .and(query_param("name", "world"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .send(&SearchHello(QueryHello {
+            name: "world".into(),
+        }))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn absolute_endpoint() {
+    let _ = env_logger::try_init();
+    let server = server("/next/page").await;
+    let client = Client::new("http://example.invalid/api");
+
+    client
+        .send(&Endpoint(format!("{}/next/page", server.uri())))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn absolute_endpoint_on_other_origin_is_not_authorized() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new("http://example.invalid/api").bearer_auth("TOKEN");
+
+    Mock::given(method("GET"))
+        .and(path("/next/page"))
+        .and(MissingHeader::new("Authorization"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .send(&Endpoint(format!("{}/next/page", server.uri())))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn absolute_endpoint_on_same_origin_is_authorized() {
+    let _ = env_logger::try_init();
+    let server = MockServer::start().await;
+    let client = Client::new(format!("{}/api", server.uri())).bearer_auth("TOKEN");
+
+    Mock::given(method("GET"))
+        .and(path("/next/page"))
+        .and(header("Authorization", "Bearer TOKEN"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client
+        .send(&Endpoint(format!("{}/next/page", server.uri())))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn endpoint_with_colon() {
+    let _ = env_logger::try_init();
+    let server = server("/api/items:batchGet").await;
+    let client = Client::new(format!("{}/api", server.uri()));
+
+    client
+        .send(&Endpoint("/items:batchGet".into()))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn non_http_base_url() {
+    let _ = env_logger::try_init();
+    let client = Client::new("file:///api");
+
+    assert!(matches!(
+        client.send(&EmptyHello).await.unwrap_err(),
+        Error::InvalidUrl { .. }
+    ));
+}
+
+#[tokio::test]
+async fn invalid_base_url() {
+    let _ = env_logger::try_init();
+    let client = Client::new("not a url");
+
+    assert!(matches!(
+        client.send(&EmptyHello).await.unwrap_err(),
+        Error::InvalidUrl { .. }
+    ));
+}
+
+#[tokio::test]
+async fn path_template() {
+    let _ = env_logger::try_init();
+    let server = server("/api/users/a%2Fb%20c%C3%A9/posts/42").await;
+    let client = Client::new(format!("{}/api", server.uri()));
+
+    let req = UserPost {
+        user: "a/b cé",
+        post: Some(42),
+    };
+    client.send(&req).await.unwrap();
+}
+
+#[tokio::test]
+async fn path_template_missing_param() {
+    let _ = env_logger::try_init();
+    let client = Client::new("http://example.invalid");
+
+    let req = UserPost {
+        user: "vila",
+        post: None,
+    };
+    match client.send(&req).await.unwrap_err() {
+        Error::MissingPathParam { name } => assert_eq!(name, "post_id"),
+        e => panic!("unexpected error: {}", e),
+    }
+}
+
+#[tokio::test]
+async fn path_template_dot_segment() {
+    let _ = env_logger::try_init();
+    let client = Client::new("http://example.invalid");
+
+    let req = UserPost {
+        user: "..",
+        post: Some(1),
+    };
+    assert!(matches!(
+        client.send(&req).await.unwrap_err(),
+        Error::InvalidUrl { .. }
+    ));
+}
@@ -13,3 +13,17 @@ impl Match for MissingQuery<'_> {
         !request.url.query_pairs().any(|(k, _)| k == self.0)
     }
 }
+
+pub struct MissingHeader<'a>(&'a str);
+
+impl<'a> MissingHeader<'a> {
+    pub fn new(header: &'a str) -> Self {
+        Self(header)
+    }
+}
+
+impl Match for MissingHeader<'_> {
+    fn matches(&self, request: &Request) -> bool {
+        !request.headers.contains_key(&self.0.into())
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use vila::pagination::query::{QueryModifier, QueryPaginator};
+use vila::pagination::PaginatedRequest;
 use vila::{EmptyResponse, Request, RequestData};
 
 pub mod matchers;
@@ -10,7 +13,7 @@ impl Request for EmptyHello {
     type Data = ();
     type Response = EmptyResponse;
 
-    fn endpoint(&self) -> Cow<str> {
+    fn endpoint(&self) -> Cow<'_, str> {
         "/hello".into()
     }
 }
@@ -39,7 +42,7 @@ impl Request for QueryHello {
     type Data = Self;
     type Response = NameGreeting;
 
-    fn endpoint(&self) -> Cow<str> {
+    fn endpoint(&self) -> Cow<'_, str> {
         "/hello".into()
     }
 
@@ -52,7 +55,7 @@ impl Request for JsonHello {
     type Data = Self;
     type Response = NameGreeting;
 
-    fn endpoint(&self) -> Cow<str> {
+    fn endpoint(&self) -> Cow<'_, str> {
         "/hello".into()
     }
 
@@ -65,7 +68,7 @@ impl Request for FormHello {
     type Data = Self;
     type Response = NameGreeting;
 
-    fn endpoint(&self) -> Cow<str> {
+    fn endpoint(&self) -> Cow<'_, str> {
         "/hello".into()
     }
 
@@ -73,3 +76,39 @@ impl Request for FormHello {
         RequestData::Form(self)
     }
 }
+
+pub struct PagedHello;
+
+#[derive(Clone)]
+pub struct Page(pub usize);
+
+#[derive(Deserialize)]
+pub struct PageResponse {
+    pub next_page: Option<usize>,
+}
+
+impl From<Page> for QueryModifier {
+    fn from(page: Page) -> QueryModifier {
+        let mut data = HashMap::new();
+        data.insert("page".into(), page.0.to_string());
+        QueryModifier { data }
+    }
+}
+
+impl Request for PagedHello {
+    type Data = ();
+    type Response = PageResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/page".into()
+    }
+}
+
+impl PaginatedRequest for PagedHello {
+    type Data = Page;
+    type Paginator = QueryPaginator<PageResponse, Page>;
+
+    fn paginator(&self) -> Self::Paginator {
+        QueryPaginator::new(|_, r: &PageResponse| r.next_page.map(Page))
+    }
+}
@@ -0,0 +1,208 @@
+//! Runs in its own test binary, so that the captured log output isn't shared with the
+//! integration tests.
+use log::{LevelFilter, Log, Metadata, Record};
+use std::borrow::Cow;
+use std::sync::Mutex;
+use vila::header::{HeaderMap, HeaderValue};
+use vila::{Client, EmptyResponse, Request, RequestData};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+static LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Capture;
+
+impl Log for Capture {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        if record.target().starts_with("vila") {
+            LOGS.lock().unwrap().push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+struct Secrets;
+
+impl Request for Secrets {
+    type Data = [(&'static str, &'static str); 2];
+    type Response = EmptyResponse;
+
+    fn endpoint(&self) -> Cow<'_, str> {
+        "/secrets".into()
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let mut secret = HeaderValue::from_static("marked-secret");
+        secret.set_sensitive(true);
+        headers.insert("X-Marked", secret);
+        headers.insert("X-Session", HeaderValue::from_static("session-secret"));
+        headers.insert("X-Visible", HeaderValue::from_static("visible"));
+        headers
+    }
+
+    fn data(&self) -> RequestData<&Self::Data> {
+        RequestData::Query(&[("signature", "query-secret"), ("page", "1")])
+    }
+}
+
+#[tokio::test]
+async fn secrets_are_redacted_in_logs() {
+    log::set_logger(&Capture).unwrap();
+    log::set_max_level(LevelFilter::Debug);
+    let server = MockServer::start().await;
+    let client = Client::new(server.uri())
+        .query_auth(vec![("api_key", "auth-secret")])
+        .redact_query_param("signature")
+        .redact_header("X-Session");
+
+    Mock::given(method("GET"))
+        .and(path("/secrets"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    client.send(&Secrets).await.unwrap();
+
+    let logs = LOGS.lock().unwrap().join("\n");
+    let sent = logs
+        .lines()
+        .find(|line| line.starts_with("Sending request"))
+        .unwrap();
+    assert!(sent.contains("/secrets?signature=***&page=1&api_key=***"));
+    assert!(sent.contains(r#""x-marked": "***""#));
+    assert!(sent.contains(r#""x-session": "***""#));
+    assert!(sent.contains(r#""x-visible": "visible""#));
+    assert!(!logs.contains("secret\""));
+    assert!(!logs.contains("auth-secret"));
+    assert!(!logs.contains("query-secret"));
+}
+
+#[tokio::test]
+async fn secrets_are_not_included_in_errors() {
+    // Nothing listens on the discard port, so the connection is refused
+    let client = Client::new("http://127.0.0.1:9").query_auth(vec![("api_key", "auth-secret")]);
+
+    let err = client.send(&Secrets).await.unwrap_err();
+    let formatted = format!("{} {:?}", err, err);
+    assert!(!formatted.contains("auth-secret"));
+    assert!(!formatted.contains("query-secret"));
+}
+
+#[cfg(feature = "progress")]
+mod progress {
+    use futures::StreamExt;
+    use indicatif::{MultiProgress, ProgressDrawTarget, TermLike};
+    use serde::Deserialize;
+    use std::borrow::Cow;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use vila::pagination::query::{QueryModifier, QueryPaginator};
+    use vila::pagination::PaginatedRequest;
+    use vila::{Client, Request, RequestData};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// A terminal that keeps everything written to it.
+    #[derive(Clone, Debug, Default)]
+    struct Output(Arc<Mutex<String>>);
+
+    impl TermLike for Output {
+        fn width(&self) -> u16 {
+            200
+        }
+
+        fn move_cursor_up(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_down(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_right(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_left(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write_line(&self, s: &str) -> io::Result<()> {
+            self.write_str(s)?;
+            self.write_str("\n")
+        }
+
+        fn write_str(&self, s: &str) -> io::Result<()> {
+            self.0.lock().unwrap().push_str(s);
+            Ok(())
+        }
+
+        fn clear_line(&self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn flush(&self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct Page {}
+
+    struct PagedSecrets;
+
+    impl Request for PagedSecrets {
+        type Data = [(&'static str, &'static str); 2];
+        type Response = Page;
+
+        fn endpoint(&self) -> Cow<'_, str> {
+            "/secrets".into()
+        }
+
+        fn data(&self) -> RequestData<&Self::Data> {
+            RequestData::Query(&[("signature", "query-secret"), ("page", "1")])
+        }
+    }
+
+    impl PaginatedRequest for PagedSecrets {
+        type Data = QueryModifier;
+        type Paginator = QueryPaginator<Page, QueryModifier>;
+
+        fn paginator(&self) -> Self::Paginator {
+            QueryPaginator::new(|_, _: &Page| None)
+        }
+    }
+
+    #[tokio::test]
+    async fn secrets_are_redacted_in_progress() {
+        let server = MockServer::start().await;
+        let output = Output::default();
+        let progress = MultiProgress::with_draw_target(ProgressDrawTarget::term_like(Box::new(
+            output.clone(),
+        )));
+        let client = Client::new(server.uri())
+            .query_auth(vec![("api_key", "auth-secret")])
+            .redact_query_param("signature")
+            .with_progress(progress);
+
+        Mock::given(method("GET"))
+            .and(path("/secrets"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("{}"))
+            .mount(&server)
+            .await;
+
+        let pages: Vec<_> = client.send_paginated(&PagedSecrets).collect().await;
+        assert_eq!(pages.len(), 1);
+
+        let output = output.0.lock().unwrap();
+        assert!(output.contains("/secrets?signature=***&page=1&api_key=***"));
+        assert!(!output.contains("auth-secret"));
+        assert!(!output.contains("query-secret"));
+    }
+}
@@ -0,0 +1,57 @@
+use syn::meta::ParseNestedMeta;
+use syn::{PathArguments, Result, Type};
+
+/// Set an attribute value, failing if it was already set.
+pub(crate) fn set<T>(meta: &ParseNestedMeta<'_>, slot: &mut Option<T>, value: T) -> Result<()> {
+    if slot.is_some() {
+        return Err(meta.error("duplicate attribute"));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// The names of the `{name}` parameters in a path template, following the same rules as vila.
+pub(crate) fn template_params(template: &str) -> Vec<String> {
+    let mut params = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        if let Some(name) = rest
+            .find('}')
+            .map(|end| &rest[..end])
+            .filter(|name| is_param_name(name))
+        {
+            params.push(name.to_string());
+            rest = &rest[name.len() + 1..];
+        }
+    }
+    params
+}
+
+pub(crate) fn is_param_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+pub(crate) fn is_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+/// Whether the type is an `Option`, in which case the field is skipped when it's `None`.
+pub(crate) fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => {
+            ty.qself.is_none()
+                && ty.path.segments.last().is_some_and(|segment| {
+                    segment.ident == "Option"
+                        && matches!(segment.arguments, PathArguments::AngleBracketed(_))
+                })
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,124 @@
+//! Derive macros for [vila](https://docs.rs/vila). The macros are re-exported by vila when the
+//! `derive` feature is enabled, and shouldn't be used through this crate directly.
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attr;
+mod paginate;
+mod request;
+
+/// Derive `vila::Request` for a struct.
+///
+/// The struct is configured through `#[vila(...)]`:
+///
+/// - `path = "/users/{id}"`: the endpoint, required. Each `{name}` parameter must be bound by a
+///   field marked `#[vila(path)]`.
+/// - `method = "POST"`: the HTTP method, defaults to `GET`.
+/// - `response = User`: the response type, defaults to `vila::EmptyResponse`.
+/// - `body = json` or `body = form`: how the body field is encoded, defaults to `json`.
+///
+/// Each field can be marked with one of:
+///
+/// - `#[vila(path)]` or `#[vila(path = "name")]`: bound to the path parameter with the name of
+///   the field, or the given name. The value is percent-encoded as a single path segment.
+/// - `#[vila(query)]` or `#[vila(query = "name")]`: sent as a query parameter.
+/// - `#[vila(header)]` or `#[vila(header = "X-Name")]`: sent as a header. The header name
+///   defaults to the field name with underscores replaced by dashes.
+/// - `#[vila(body)]`: sent as the body of the request. At most one field can be the body.
+///
+/// Path, query and header values are formatted through `Display`, and `Option` fields are
+/// skipped when they are `None`. Fields without an attribute aren't sent.
+///
+/// Header fields whose value isn't a valid header value, e.g. because it contains a newline, are
+/// skipped with a warning.
+///
+/// # Example
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use vila::Request;
+///
+/// #[derive(Serialize)]
+/// struct NewPost {
+///     title: String,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Post {
+///     id: u64,
+/// }
+///
+/// #[derive(Request)]
+/// #[vila(method = "POST", path = "/users/{id}/posts", response = Post)]
+/// struct CreatePost {
+///     #[vila(path)]
+///     id: u64,
+///     #[vila(query)]
+///     dry_run: bool,
+///     #[vila(header = "X-Request-Id")]
+///     request_id: Option<String>,
+///     #[vila(body)]
+///     post: NewPost,
+/// }
+/// ```
+#[proc_macro_derive(Request, attributes(vila))]
+pub fn derive_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    request::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `vila::pagination::PaginatedRequest` for a struct that implements `vila::Request`.
+///
+/// The pagination style is configured through `#[paginate(...)]`, and pages are requested through
+/// query parameters, with `vila::pagination::query::QueryModifier` as the pagination data.
+///
+/// Page numbers:
+///
+/// - `query = "page"`: the query parameter holding the page number.
+/// - `start = 1`: the number of the first page, defaults to 1.
+/// - `until = "total_pages"`: the response field with the total number of pages, e.g.
+///   `meta.total_pages` for a nested field. Pagination stops once that many pages have been
+///   fetched.
+///
+/// Cursors:
+///
+/// - `cursor = "next_cursor"`: the response field with the cursor for the next page. The field
+///   must be an `Option`, and pagination stops when it's `None` or empty.
+/// - `param = "cursor"`: the query parameter the cursor is sent in, defaults to `cursor`.
+///
+/// # Example
+///
+/// ```
+/// use serde::Deserialize;
+/// use vila::pagination::PaginatedRequest;
+/// use vila::Request;
+///
+/// #[derive(Deserialize)]
+/// struct Users {
+///     total_pages: u32,
+/// }
+///
+/// #[derive(Request, PaginatedRequest)]
+/// #[vila(path = "/users", response = Users)]
+/// #[paginate(query = "page", start = 1, until = "total_pages")]
+/// struct ListUsers;
+///
+/// #[derive(Deserialize)]
+/// struct Events {
+///     next_cursor: Option<String>,
+/// }
+///
+/// #[derive(Request, PaginatedRequest)]
+/// #[vila(path = "/events", response = Events)]
+/// #[paginate(cursor = "next_cursor", param = "after")]
+/// struct ListEvents;
+/// ```
+#[proc_macro_derive(PaginatedRequest, attributes(paginate))]
+pub fn derive_paginated_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    paginate::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,193 @@
+use crate::attr::set;
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{DeriveInput, Error, Ident, LitInt, LitStr, Result};
+
+/// The `#[paginate(...)]` attributes on the struct.
+#[derive(Default)]
+struct Attrs {
+    query: Option<LitStr>,
+    start: Option<LitInt>,
+    until: Option<LitStr>,
+    cursor: Option<LitStr>,
+    param: Option<LitStr>,
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let attrs = parse_attrs(input)?;
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let modifier = quote!(::vila::pagination::query::QueryModifier);
+    let response = quote!(<Self as ::vila::Request>::Response);
+
+    let (paginator, initial_page) = match (&attrs.query, &attrs.cursor) {
+        (Some(query), None) => page_number(&attrs, query)?,
+        (None, Some(cursor)) => cursor_paginator(&attrs, cursor)?,
+        (Some(query), Some(_)) => {
+            return Err(Error::new(
+                query.span(),
+                "use either `query` for page numbers or `cursor` for cursors, not both",
+            ))
+        }
+        (None, None) => {
+            return Err(Error::new(
+                input.ident.span(),
+                "missing pagination style, add `#[paginate(query = \"...\", until = \"...\")]` or `#[paginate(cursor = \"...\")]` to the struct",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::vila::pagination::PaginatedRequest for #ident #ty_generics #where_clause {
+            type Data = #modifier;
+            type Paginator = ::vila::pagination::query::QueryPaginator<#response, #modifier>;
+
+            fn paginator(&self) -> Self::Paginator {
+                ::vila::pagination::query::QueryPaginator::new(
+                    |prev: ::std::option::Option<&#modifier>, res: &#response| #paginator
+                )
+            }
+
+            #initial_page
+        }
+    })
+}
+
+fn parse_attrs(input: &DeriveInput) -> Result<Attrs> {
+    let mut attrs = Attrs::default();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("paginate")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("query") {
+                let lit = meta.value()?.parse()?;
+                set(&meta, &mut attrs.query, lit)
+            } else if meta.path.is_ident("start") {
+                let lit: LitInt = meta.value()?.parse()?;
+                lit.base10_parse::<u64>()?;
+                set(&meta, &mut attrs.start, lit)
+            } else if meta.path.is_ident("until") {
+                let lit = meta.value()?.parse()?;
+                set(&meta, &mut attrs.until, lit)
+            } else if meta.path.is_ident("cursor") {
+                let lit = meta.value()?.parse()?;
+                set(&meta, &mut attrs.cursor, lit)
+            } else if meta.path.is_ident("param") {
+                let lit = meta.value()?.parse()?;
+                set(&meta, &mut attrs.param, lit)
+            } else {
+                Err(meta.error(
+                    "unknown attribute, expected one of `query`, `start`, `until`, `cursor` or `param`",
+                ))
+            }
+        })?;
+    }
+    Ok(attrs)
+}
+
+/// Page numbers sent in the query parameter `query`, counting up from `start` until as many pages
+/// as the response field `until` reports have been fetched.
+fn page_number(attrs: &Attrs, query: &LitStr) -> Result<(TokenStream, TokenStream)> {
+    if let Some(param) = &attrs.param {
+        return Err(Error::new(
+            param.span(),
+            "`param` is only used with `cursor`, the page number is sent in the `query` parameter",
+        ));
+    }
+    let until = attrs.until.as_ref().ok_or_else(|| {
+        Error::new(
+            query.span(),
+            "`query` pagination requires `until`, the response field with the total number of pages",
+        )
+    })?;
+    let total = response_field(until)?;
+    let start = match &attrs.start {
+        Some(start) => start.base10_parse::<u64>()?,
+        None => 1,
+    };
+    let modifier = modifier(query, quote!(page));
+
+    let paginator = quote! {{
+        let page: u64 = prev
+            .and_then(|prev| prev.data.get(#query))
+            .and_then(|page| page.parse().ok())
+            .unwrap_or(#start);
+        let total: u64 = ::std::convert::TryFrom::try_from(#total).unwrap_or(0);
+        if page.saturating_sub(#start) + 1 >= total {
+            return ::std::option::Option::None;
+        }
+        let page = page + 1;
+        ::std::option::Option::Some(#modifier)
+    }};
+    let initial_page = quote! {
+        fn initial_page(&self) -> ::std::option::Option<::vila::pagination::query::QueryModifier> {
+            let page: u64 = #start;
+            ::std::option::Option::Some(#modifier)
+        }
+    };
+    Ok((paginator, initial_page))
+}
+
+/// Cursors taken from the optional response field `cursor` and sent in the query parameter
+/// `param`, until the response has no cursor.
+fn cursor_paginator(attrs: &Attrs, cursor: &LitStr) -> Result<(TokenStream, TokenStream)> {
+    if let Some(start) = &attrs.start {
+        return Err(Error::new(
+            start.span(),
+            "`start` is only used with `query` page numbers",
+        ));
+    }
+    if let Some(until) = &attrs.until {
+        return Err(Error::new(
+            until.span(),
+            "`until` is only used with `query` page numbers",
+        ));
+    }
+    let next = response_field(cursor)?;
+    let param = attrs
+        .param
+        .clone()
+        .unwrap_or_else(|| LitStr::new("cursor", cursor.span()));
+    let modifier = modifier(&param, quote!(cursor));
+
+    let paginator = quote! {{
+        let _ = prev;
+        let cursor = ::std::option::Option::as_ref(&#next)?;
+        let cursor = ::std::string::ToString::to_string(cursor);
+        if cursor.is_empty() {
+            return ::std::option::Option::None;
+        }
+        ::std::option::Option::Some(#modifier)
+    }};
+    Ok((paginator, TokenStream::new()))
+}
+
+/// A `QueryModifier` setting the query parameter `name` to `value`.
+fn modifier(name: &LitStr, value: TokenStream) -> TokenStream {
+    quote! {{
+        let mut data = ::std::collections::HashMap::new();
+        data.insert(
+            ::std::string::ToString::to_string(#name),
+            ::std::string::ToString::to_string(&#value),
+        );
+        ::vila::pagination::query::QueryModifier { data }
+    }}
+}
+
+/// Access a field of the response, e.g. `meta.total_pages`.
+fn response_field(lit: &LitStr) -> Result<TokenStream> {
+    let fields = lit
+        .value()
+        .split('.')
+        .map(|field| {
+            let mut field = syn::parse_str::<Ident>(field).map_err(|_| {
+                Error::new(
+                    lit.span(),
+                    format!("invalid response field {:?}", lit.value()),
+                )
+            })?;
+            // Point errors about missing fields at the attribute
+            field.set_span(lit.span());
+            Ok(field)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(quote_spanned! {lit.span()=> res #(.#fields)*})
+}
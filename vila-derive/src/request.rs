@@ -0,0 +1,369 @@
+use crate::attr::{self, is_param_name, set};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Error, Field, Fields, Ident, LitStr, Result, Token, Type};
+
+const METHODS: &[&str] = &[
+    "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "CONNECT", "TRACE",
+];
+
+/// The `#[vila(...)]` attributes on the struct.
+#[derive(Default)]
+struct Attrs {
+    method: Option<Ident>,
+    path: Option<LitStr>,
+    response: Option<Type>,
+    body: Option<Ident>,
+}
+
+/// What a field is sent as.
+enum Role {
+    Path(String),
+    Query(String),
+    Header(String),
+    Body,
+}
+
+struct RequestField<'a> {
+    field: &'a Field,
+    ident: &'a Ident,
+    role: Role,
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let attrs = parse_attrs(input)?;
+    let fields = fields(input)?;
+
+    let path = attrs.path.as_ref().ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "missing endpoint, add `#[vila(path = \"...\")]` to the struct",
+        )
+    })?;
+    check_path_params(path, &fields)?;
+
+    let mut body = None;
+    for field in &fields {
+        if let Role::Body = field.role {
+            if body.is_some() {
+                return Err(Error::new(
+                    field.ident.span(),
+                    "only one field can be marked `#[vila(body)]`, use a struct to send several fields in the body",
+                ));
+            }
+            body = Some(field);
+        }
+    }
+    if let (Some(format), None) = (&attrs.body, body) {
+        return Err(Error::new(
+            format.span(),
+            format!(
+                "`body = {}` requires a field marked `#[vila(body)]`",
+                format
+            ),
+        ));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let method = attrs.method.as_ref().map(|method| {
+        quote! { const METHOD: ::vila::Method = ::vila::Method::#method; }
+    });
+    let response = match &attrs.response {
+        Some(response) => quote!(#response),
+        None => quote!(::vila::EmptyResponse),
+    };
+    let data = match body {
+        Some(body) => {
+            let ty = &body.field.ty;
+            quote_spanned! { ty.span()=> #ty }
+        }
+        None => quote!(()),
+    };
+    let data_fn = body.map(|body| {
+        let field = body.ident;
+        let variant = match attrs.body.as_ref().map(|f| f.to_string()).as_deref() {
+            Some("form") => format_ident!("Form"),
+            _ => format_ident!("Json"),
+        };
+        quote! {
+            fn data(&self) -> ::vila::RequestData<&Self::Data> {
+                ::vila::RequestData::#variant(&self.#field)
+            }
+        }
+    });
+    let path_params_fn = path_params_fn(&fields);
+    let query_fn = query_fn(&fields);
+    let headers_fn = headers_fn(&fields);
+
+    Ok(quote! {
+        impl #impl_generics ::vila::Request for #ident #ty_generics #where_clause {
+            type Data = #data;
+            type Response = #response;
+            #method
+
+            fn endpoint(&self) -> ::std::borrow::Cow<'_, str> {
+                ::std::borrow::Cow::Borrowed(#path)
+            }
+
+            #path_params_fn
+            #query_fn
+            #headers_fn
+            #data_fn
+        }
+    })
+}
+
+fn parse_attrs(input: &DeriveInput) -> Result<Attrs> {
+    let mut attrs = Attrs::default();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("vila")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("method") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let method = lit.value().to_uppercase();
+                if !METHODS.contains(&method.as_str()) {
+                    return Err(Error::new(
+                        lit.span(),
+                        format!(
+                            "unknown HTTP method {:?}, expected one of {}",
+                            lit.value(),
+                            METHODS.join(", ")
+                        ),
+                    ));
+                }
+                set(&meta, &mut attrs.method, Ident::new(&method, lit.span()))
+            } else if meta.path.is_ident("path") {
+                let lit = meta.value()?.parse()?;
+                set(&meta, &mut attrs.path, lit)
+            } else if meta.path.is_ident("response") {
+                let ty = meta.value()?.parse()?;
+                set(&meta, &mut attrs.response, ty)
+            } else if meta.path.is_ident("body") {
+                let format: Ident = meta.value()?.parse()?;
+                if format != "json" && format != "form" {
+                    return Err(Error::new(
+                        format.span(),
+                        format!(
+                            "unknown body format `{}`, expected `json` or `form`",
+                            format
+                        ),
+                    ));
+                }
+                set(&meta, &mut attrs.body, format)
+            } else {
+                Err(meta.error(
+                    "unknown attribute, expected one of `method`, `path`, `response` or `body`",
+                ))
+            }
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn fields(input: &DeriveInput) -> Result<Vec<RequestField<'_>>> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "`#[derive(Request)]` only supports structs",
+            ))
+        }
+    };
+    let fields = match fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unit => return Ok(Vec::new()),
+        Fields::Unnamed(_) => {
+            return Err(Error::new(
+                input.ident.span(),
+                "`#[derive(Request)]` only supports structs with named fields",
+            ))
+        }
+    };
+    let mut request_fields = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        if let Some(role) = field_role(field, ident)? {
+            request_fields.push(RequestField { field, ident, role });
+        }
+    }
+    Ok(request_fields)
+}
+
+fn field_role(field: &Field, ident: &Ident) -> Result<Option<Role>> {
+    let mut role = None;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("vila")) {
+        attr.parse_nested_meta(|meta| {
+            let name = || -> Result<Option<String>> {
+                if meta.input.peek(Token![=]) {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    Ok(Some(lit.value()))
+                } else {
+                    Ok(None)
+                }
+            };
+            let field_name = ident.unraw().to_string();
+            let new = if meta.path.is_ident("path") {
+                Role::Path(name()?.unwrap_or(field_name))
+            } else if meta.path.is_ident("query") {
+                Role::Query(name()?.unwrap_or(field_name))
+            } else if meta.path.is_ident("header") {
+                let header = name()?.unwrap_or_else(|| field_name.replace('_', "-"));
+                if !attr::is_header_name(&header) {
+                    return Err(meta.error(format!("invalid header name {:?}", header)));
+                }
+                Role::Header(header.to_lowercase())
+            } else if meta.path.is_ident("body") {
+                Role::Body
+            } else {
+                return Err(meta.error(
+                    "unknown attribute, expected one of `path`, `query`, `header` or `body`",
+                ));
+            };
+            if role.is_some() {
+                return Err(
+                    meta.error("a field can only be one of `path`, `query`, `header` or `body`")
+                );
+            }
+            role = Some(new);
+            Ok(())
+        })?;
+    }
+    Ok(role)
+}
+
+/// Check that the parameters in the path template and the fields marked `#[vila(path)]` match.
+fn check_path_params(path: &LitStr, fields: &[RequestField<'_>]) -> Result<()> {
+    let template = path.value();
+    let params = attr::template_params(&template);
+    let path_fields: Vec<_> = fields
+        .iter()
+        .filter_map(|f| match &f.role {
+            Role::Path(name) => Some((f, name)),
+            _ => None,
+        })
+        .collect();
+    for param in &params {
+        if !path_fields.iter().any(|(_, name)| *name == param) {
+            return Err(Error::new(
+                path.span(),
+                format!(
+                    "path parameter `{{{}}}` has no field marked `#[vila(path)]`",
+                    param
+                ),
+            ));
+        }
+    }
+    for (i, (field, name)) in path_fields.iter().enumerate() {
+        if !is_param_name(name) || !params.contains(name) {
+            return Err(Error::new(
+                field.ident.span(),
+                format!(
+                    "`{{{}}}` is not a parameter in the path {:?}",
+                    name, template
+                ),
+            ));
+        }
+        if path_fields[..i].iter().any(|(_, other)| other == name) {
+            return Err(Error::new(
+                field.ident.span(),
+                format!(
+                    "path parameter `{{{}}}` is bound by more than one field",
+                    name
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn path_params_fn(fields: &[RequestField<'_>]) -> Option<TokenStream> {
+    let params: Vec<_> = fields
+        .iter()
+        .filter_map(|f| match &f.role {
+            Role::Path(name) => Some(bind(f, |value| {
+                quote! { params = params.param(#name, #value); }
+            })),
+            _ => None,
+        })
+        .collect();
+    if params.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        fn path_params(&self) -> ::vila::PathParams {
+            let mut params = ::vila::PathParams::new();
+            #(#params)*
+            params
+        }
+    })
+}
+
+fn query_fn(fields: &[RequestField<'_>]) -> Option<TokenStream> {
+    let pairs: Vec<_> = fields
+        .iter()
+        .filter_map(|f| match &f.role {
+            Role::Query(name) => Some(bind(f, |value| {
+                quote! {
+                    query.push((
+                        ::std::borrow::Cow::Borrowed(#name),
+                        ::std::borrow::Cow::Owned(::std::string::ToString::to_string(#value)),
+                    ));
+                }
+            })),
+            _ => None,
+        })
+        .collect();
+    if pairs.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        fn query(&self) -> ::std::vec::Vec<(::std::borrow::Cow<'_, str>, ::std::borrow::Cow<'_, str>)> {
+            let mut query = ::std::vec::Vec::new();
+            #(#pairs)*
+            query
+        }
+    })
+}
+
+fn headers_fn(fields: &[RequestField<'_>]) -> Option<TokenStream> {
+    let headers: Vec<_> = fields
+        .iter()
+        .filter_map(|f| match &f.role {
+            Role::Header(name) => Some(bind(f, |value| {
+                quote! {
+                    ::vila::__private::insert_header(&mut headers, #name, #value);
+                }
+            })),
+            _ => None,
+        })
+        .collect();
+    if headers.is_empty() {
+        return None;
+    }
+    Some(quote! {
+        fn headers(&self) -> ::vila::header::HeaderMap {
+            let mut headers = ::vila::header::HeaderMap::new();
+            #(#headers)*
+            headers
+        }
+    })
+}
+
+/// Generate the statement for a field, skipping `Option` fields that are `None`.
+fn bind<F: Fn(TokenStream) -> TokenStream>(field: &RequestField<'_>, f: F) -> TokenStream {
+    let ident = field.ident;
+    let span = field.field.ty.span();
+    if attr::is_option(&field.field.ty) {
+        let stmt = f(quote_spanned!(span=> value));
+        quote_spanned! {span=>
+            if let ::std::option::Option::Some(value) = &self.#ident {
+                #stmt
+            }
+        }
+    } else {
+        f(quote_spanned!(span=> &self.#ident))
+    }
+}
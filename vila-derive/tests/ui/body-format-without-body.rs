@@ -0,0 +1,10 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(method = "POST", path = "/users", body = form)]
+struct CreateUser {
+    #[vila(query)]
+    name: String,
+}
+
+fn main() {}
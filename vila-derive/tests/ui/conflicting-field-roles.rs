@@ -0,0 +1,10 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(path = "/users")]
+struct ListUsers {
+    #[vila(query, header)]
+    page: u32,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(method = "GET")]
+struct ListUsers;
+
+fn main() {}
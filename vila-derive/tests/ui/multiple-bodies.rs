@@ -0,0 +1,12 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(method = "POST", path = "/users")]
+struct CreateUser {
+    #[vila(body)]
+    name: String,
+    #[vila(body)]
+    email: String,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use vila::pagination::PaginatedRequest;
+use vila::Request;
+
+#[derive(Deserialize)]
+struct Users {
+    total_pages: u32,
+    next: Option<String>,
+}
+
+#[derive(Request, PaginatedRequest)]
+#[vila(path = "/users", response = Users)]
+#[paginate(query = "page", until = "total_pages", cursor = "next")]
+struct ListUsers;
+
+fn main() {}
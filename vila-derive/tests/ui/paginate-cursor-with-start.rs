@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use vila::pagination::PaginatedRequest;
+use vila::Request;
+
+#[derive(Deserialize)]
+struct Events {
+    next: Option<String>,
+}
+
+#[derive(Request, PaginatedRequest)]
+#[vila(path = "/events", response = Events)]
+#[paginate(cursor = "next", start = 1)]
+struct ListEvents;
+
+fn main() {}
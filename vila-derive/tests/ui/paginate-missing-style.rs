@@ -0,0 +1,14 @@
+use serde::Deserialize;
+use vila::pagination::PaginatedRequest;
+use vila::Request;
+
+#[derive(Deserialize)]
+struct Users {
+    users: Vec<String>,
+}
+
+#[derive(Request, PaginatedRequest)]
+#[vila(path = "/users", response = Users)]
+struct ListUsers;
+
+fn main() {}
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use vila::pagination::PaginatedRequest;
+use vila::Request;
+
+#[derive(Deserialize)]
+struct Users {
+    users: Vec<String>,
+}
+
+#[derive(Request, PaginatedRequest)]
+#[vila(path = "/users", response = Users)]
+#[paginate(query = "page")]
+struct ListUsers;
+
+fn main() {}
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use vila::pagination::PaginatedRequest;
+use vila::Request;
+
+#[derive(Deserialize)]
+struct Users {
+    total_pages: u32,
+}
+
+#[derive(Request, PaginatedRequest)]
+#[vila(path = "/users", response = Users)]
+#[paginate(query = "page", until = "pages")]
+struct ListUsers;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(path = "/users/{id}")]
+struct GetUser(u64);
+
+fn main() {}
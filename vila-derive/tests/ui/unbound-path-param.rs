@@ -0,0 +1,10 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(path = "/users/{id}/posts/{post_id}")]
+struct GetPost {
+    #[vila(path)]
+    id: u64,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(path = "/users", timeout = 10)]
+struct ListUsers;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(method = "FETCH", path = "/users")]
+struct ListUsers;
+
+fn main() {}
@@ -0,0 +1,12 @@
+use vila::Request;
+
+#[derive(Request)]
+#[vila(path = "/users/{id}")]
+struct GetUser {
+    #[vila(path)]
+    id: u64,
+    #[vila(path)]
+    org: u64,
+}
+
+fn main() {}